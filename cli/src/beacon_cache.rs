@@ -0,0 +1,298 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{bail, Context, Result};
+use beacon_state::{PresetBeaconState, Version};
+use std::{
+    fs::{self, File},
+    io,
+    path::PathBuf,
+    time::SystemTime,
+};
+
+/// Default size budget of the cache. A mainnet state is roughly 250MB of SSZ.
+const DEFAULT_MAX_SIZE: &str = "16GiB";
+
+/// Options of the beacon state cache
+#[derive(clap::Args, Debug, Clone)]
+pub struct CacheArgs {
+    /// Directory finalized beacon states are cached in
+    #[clap(long, env, default_value = "./beacon-cache")]
+    beacon_cache_dir: PathBuf,
+
+    /// Size budget of the cache, e.g. `16GiB`. The least recently used states are evicted
+    /// once it is exceeded.
+    #[clap(long, env, default_value = DEFAULT_MAX_SIZE, value_parser = parse_size)]
+    beacon_cache_max_size: u64,
+}
+
+impl CacheArgs {
+    pub fn cache(&self) -> Result<StateCache> {
+        StateCache::new(&self.beacon_cache_dir, self.beacon_cache_max_size)
+    }
+}
+
+/// A cached beacon state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheEntry {
+    pub slot: u64,
+    pub fork: Version,
+    pub size: u64,
+    /// When the state was last read from or written to the cache
+    pub last_used: SystemTime,
+    pub path: PathBuf,
+}
+
+/// Slot keyed cache of finalized beacon states.
+///
+/// States are stored as `<slot>.<fork>.ssz` files so the cache directory can also be used as
+/// an archive with `--beacon-state-dir`. Only finalized states may be stored as a state at a slot
+/// can change until it is finalized. The cache is kept within its size budget by evicting
+/// the least recently used states.
+#[derive(Debug, Clone)]
+pub struct StateCache {
+    path: PathBuf,
+    max_size: u64,
+}
+
+impl StateCache {
+    pub fn new(path: impl Into<PathBuf>, max_size: u64) -> Result<Self> {
+        let path = path.into();
+        fs::create_dir_all(&path)
+            .with_context(|| format!("creating beacon cache {}", path.display()))?;
+        Ok(Self { path, max_size })
+    }
+
+    /// Reads the state at the given slot if it is cached
+    pub fn get<S: PresetBeaconState>(&self, slot: u64) -> Result<Option<S>> {
+        let Some(entry) = self.entries()?.into_iter().find(|entry| entry.slot == slot) else {
+            return Ok(None);
+        };
+        let bytes = fs::read(&entry.path)?;
+        let state = S::from_versioned_ssz(entry.fork, &bytes)?;
+        // keep recently used states from being evicted
+        File::options()
+            .write(true)
+            .open(&entry.path)?
+            .set_modified(SystemTime::now())?;
+        Ok(Some(state))
+    }
+
+    /// Stores the SSZ encoding of a finalized state and evicts states beyond the size budget.
+    /// Callers are responsible for only storing finalized states.
+    pub fn put(&self, slot: u64, fork: Version, ssz: &[u8]) -> Result<()> {
+        let path = self.path.join(format!("{slot}.{fork}.ssz"));
+        // write to a temporary file first so an interrupted write never leaves a truncated state
+        let tmp = path.with_extension("ssz.tmp");
+        fs::write(&tmp, ssz)?;
+        fs::rename(&tmp, &path)?;
+        let evicted = self.evict(self.max_size)?;
+        if !evicted.is_empty() {
+            tracing::debug!("evicted {} beacon states from the cache", evicted.len());
+        }
+        Ok(())
+    }
+
+    /// All cached states ordered by slot
+    pub fn entries(&self) -> Result<Vec<CacheEntry>> {
+        let mut entries = Vec::new();
+        for dir_entry in fs::read_dir(&self.path)? {
+            let dir_entry = dir_entry?;
+            let Some((slot, fork)) = dir_entry.file_name().to_str().and_then(parse_file_name)
+            else {
+                continue;
+            };
+            let metadata = dir_entry.metadata()?;
+            entries.push(CacheEntry {
+                slot,
+                fork,
+                size: metadata.len(),
+                last_used: metadata.modified()?,
+                path: dir_entry.path(),
+            });
+        }
+        entries.sort_by_key(|entry| entry.slot);
+        Ok(entries)
+    }
+
+    /// Evicts the least recently used states until the cache fits in `max_size` bytes.
+    /// Returns the evicted states.
+    pub fn evict(&self, max_size: u64) -> Result<Vec<CacheEntry>> {
+        let mut entries = self.entries()?;
+        entries.sort_by_key(|entry| entry.last_used);
+        let mut size: u64 = entries.iter().map(|entry| entry.size).sum();
+        let mut evicted = Vec::new();
+        for entry in entries {
+            if size <= max_size {
+                break;
+            }
+            remove(&entry)?;
+            size -= entry.size;
+            evicted.push(entry);
+        }
+        Ok(evicted)
+    }
+
+    /// Removes all states before the given slot. Returns the removed states.
+    pub fn remove_before(&self, slot: u64) -> Result<Vec<CacheEntry>> {
+        let mut removed = Vec::new();
+        for entry in self.entries()? {
+            if entry.slot < slot {
+                remove(&entry)?;
+                removed.push(entry);
+            }
+        }
+        Ok(removed)
+    }
+
+    pub fn max_size(&self) -> u64 {
+        self.max_size
+    }
+}
+
+fn remove(entry: &CacheEntry) -> Result<()> {
+    match fs::remove_file(&entry.path) {
+        // another process may have evicted it already
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+/// Parses `<slot>.<fork>.ssz`
+fn parse_file_name(name: &str) -> Option<(u64, Version)> {
+    let (slot, fork) = name.strip_suffix(".ssz")?.split_once('.')?;
+    Some((slot.parse().ok()?, fork.parse().ok()?))
+}
+
+/// Parses a size in bytes with an optional binary unit, e.g. `512MiB` or `16GiB`
+pub fn parse_size(s: &str) -> Result<u64> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (value, unit) = s.split_at(split);
+    let value: u64 = value
+        .parse()
+        .with_context(|| format!("invalid size: {s}"))?;
+    let shift = match unit.trim() {
+        "" | "B" => 0,
+        "K" | "KiB" => 10,
+        "M" | "MiB" => 20,
+        "G" | "GiB" => 30,
+        "T" | "TiB" => 40,
+        unit => bail!("unknown size unit {unit}, expected one of B, KiB, MiB, GiB or TiB"),
+    };
+    value
+        .checked_mul(1 << shift)
+        .with_context(|| format!("size out of range: {s}"))
+}
+
+/// Formats a size in bytes with the largest binary unit that keeps it above one
+pub fn format_size(size: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    let mut value = size as f64;
+    let mut unit = "B";
+    for next in UNITS {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+    if unit == "B" {
+        format!("{size}B")
+    } else {
+        format!("{value:.1}{unit}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beacon_state::mainnet::BeaconState;
+    use guest_io::network::MAINNET;
+    use std::time::Duration;
+    use test_utils::TestStateBuilder;
+
+    fn temp_cache(name: &str, max_size: u64) -> StateCache {
+        let path = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        StateCache::new(path, max_size).unwrap()
+    }
+
+    fn ssz_state(slot: u64) -> Vec<u8> {
+        let mut b = TestStateBuilder::new(slot);
+        b.with_lido_validators(1);
+        b.build().to_ssz().unwrap()
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(parse_size("1024").unwrap(), 1024);
+        assert_eq!(parse_size("512MiB").unwrap(), 512 << 20);
+        assert_eq!(parse_size("16G").unwrap(), 16 << 30);
+        assert!(parse_size("16GB").is_err());
+        assert!(parse_size("GiB").is_err());
+        assert_eq!(format_size(1000), "1000B");
+        assert_eq!(format_size(3 << 29), "1.5GiB");
+    }
+
+    #[test]
+    fn file_names() {
+        assert_eq!(
+            parse_file_name("11649024.electra.ssz"),
+            Some((11649024, Version::Electra))
+        );
+        assert_eq!(parse_file_name("11649024.electra.ssz.tmp"), None);
+        assert_eq!(parse_file_name("head.electra.ssz"), None);
+    }
+
+    #[test]
+    fn put_and_get() {
+        let slot = MAINNET.electra_fork_slot.unwrap();
+        let cache = temp_cache("beacon-cache-put-get", u64::MAX);
+        assert!(cache.get::<BeaconState>(slot).unwrap().is_none());
+
+        cache.put(slot, Version::Electra, &ssz_state(slot)).unwrap();
+        let state: BeaconState = cache.get(slot).unwrap().unwrap();
+        assert_eq!(state.slot(), slot);
+        assert_eq!(state.validators().len(), 1);
+        assert!(cache.get::<BeaconState>(slot + 1).unwrap().is_none());
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let slot = MAINNET.electra_fork_slot.unwrap();
+        let size = ssz_state(slot).len() as u64;
+        let cache = temp_cache("beacon-cache-evict", 2 * size);
+
+        cache.put(slot, Version::Electra, &ssz_state(slot)).unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+        cache
+            .put(slot + 1, Version::Electra, &ssz_state(slot + 1))
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+        // reading the oldest state makes the second one the least recently used
+        cache.get::<BeaconState>(slot).unwrap().unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+        cache
+            .put(slot + 2, Version::Electra, &ssz_state(slot + 2))
+            .unwrap();
+
+        let slots: Vec<_> = cache.entries().unwrap().iter().map(|e| e.slot).collect();
+        assert_eq!(slots, vec![slot, slot + 2]);
+
+        let removed = cache.remove_before(slot + 2).unwrap();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(cache.entries().unwrap().len(), 1);
+    }
+}
@@ -12,17 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use alloy::hex::FromHex;
+use crate::beacon_cache::StateCache;
 use alloy_primitives::B256;
-use beacon_state::mainnet::BeaconState;
+use beacon_state::{PresetBeaconState, Version};
+use bytes::Bytes;
 use ethereum_consensus::{
-    phase0::SignedBeaconBlockHeader, primitives::Root, types::mainnet::BeaconBlock, Fork,
+    phase0::{Checkpoint, SignedBeaconBlockHeader},
+    primitives::Root,
+    serde::as_str,
+    ssz::prelude::{HashTreeRoot, MerkleizationError},
+    types::mainnet::BeaconBlock,
 };
-use http_cache_reqwest::{CACacheManager, Cache, CacheMode, HttpCache, HttpCacheOptions};
-use reqwest::IntoUrl;
-use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use reqwest::{
+    header::{HeaderMap, ACCEPT, CONTENT_TYPE},
+    IntoUrl, StatusCode,
+};
+use reqwest_middleware::ClientWithMiddleware;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, fmt::Display};
+use std::{collections::HashMap, fmt::Display, marker::PhantomData, time::Duration};
 use url::Url;
 
 /// Errors returned by the [BeaconClient].
@@ -34,10 +41,55 @@ pub enum Error {
     Http(#[from] reqwest::Error),
     #[error("JSON request middleware failed: {0}")]
     Middleware(#[from] reqwest_middleware::Error),
-    #[error("version field does not match data version")]
-    VersionMismatch,
+    #[error("could not decode beacon state: {0}")]
+    BeaconState(#[from] beacon_state::Error),
+    #[error("SSZ response is missing the Eth-Consensus-Version header")]
+    MissingConsensusVersion,
+    #[error("unsupported consensus version: {0}")]
+    UnsupportedConsensusVersion(String),
+    #[error("no beacon block found for {0}, the slot may have been missed")]
+    BlockNotFound(String),
+    #[error("beacon block {0} has no execution payload as it predates the merge")]
+    PreMerge(String),
+    #[error("no beacon endpoints configured")]
+    NoEndpoints,
+    #[error("beacon endpoint {url} returned {status}")]
+    Status { url: Url, status: StatusCode },
+    #[error("could not decode JSON response: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("could not merkleize beacon state: {0}")]
+    Merkleization(#[from] MerkleizationError),
+    #[error("beacon endpoints {first} and {other} disagree on the root of block {block_id}")]
+    BlockRootMismatch {
+        block_id: String,
+        first: Url,
+        other: Url,
+    },
+    #[error("latest block of the state at slot {slot} has root {block_root:?} but the endpoints report {expected:?}")]
+    StateMismatch {
+        slot: u64,
+        block_root: Root,
+        expected: Root,
+    },
+}
+
+impl Error {
+    /// Whether the request failed to connect or timed out, which may not happen again.
+    /// Errors building or sending the request are not retried as they would fail the same way.
+    fn is_transient(&self) -> bool {
+        match self {
+            Error::Http(err) | Error::Middleware(reqwest_middleware::Error::Reqwest(err)) => {
+                err.is_timeout() || err.is_connect()
+            }
+            _ => false,
+        }
+    }
 }
 
+/// Header carrying the fork of SSZ encoded responses
+const CONSENSUS_VERSION_HEADER: &str = "Eth-Consensus-Version";
+const SSZ_CONTENT_TYPE: &str = "application/octet-stream";
+
 /// Response returned by the `get_block_header` API.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetBlockHeaderResponse {
@@ -46,12 +98,53 @@ pub struct GetBlockHeaderResponse {
     pub header: SignedBeaconBlockHeader,
 }
 
+/// Response returned by the `get_block_root` API.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetBlockRootResponse {
+    pub root: Root,
+}
+
 /// Response returned by the `get_block_header` API.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetBlockResponse {
     pub message: BeaconBlock,
 }
 
+/// Response returned by the `get_state_finality_checkpoints` API.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetFinalityCheckpointsResponse {
+    pub previous_justified: Checkpoint,
+    pub current_justified: Checkpoint,
+    pub finalized: Checkpoint,
+}
+
+/// Fields of the execution payload carried by a beacon block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionPayloadHeader {
+    pub block_hash: B256,
+    #[serde(with = "as_str")]
+    pub block_number: u64,
+    #[serde(with = "as_str")]
+    pub timestamp: u64,
+}
+
+/// The parts of the `get_block` response needed to read the execution payload.
+/// Blocks from before Bellatrix have no payload at all.
+#[derive(Deserialize)]
+struct ExecutionPayloadBlock {
+    message: ExecutionPayloadMessage,
+}
+
+#[derive(Deserialize)]
+struct ExecutionPayloadMessage {
+    body: ExecutionPayloadBody,
+}
+
+#[derive(Deserialize)]
+struct ExecutionPayloadBody {
+    execution_payload: Option<ExecutionPayloadHeader>,
+}
+
 /// Wrapper returned by the API calls.
 #[derive(Serialize, Deserialize)]
 struct Response<T> {
@@ -63,88 +156,411 @@ struct Response<T> {
 /// Wrapper returned by the API calls that includes a version.
 #[derive(Serialize, Deserialize)]
 struct VersionedResponse<T> {
-    version: Fork,
+    version: Version,
     #[serde(flatten)]
     inner: Response<T>,
 }
 
-/// Simple beacon API client for the `mainnet` preset that can query headers and blocks.
-pub struct BeaconClient {
+/// Retry policy applied to each beacon endpoint before failing over to the next one
+#[derive(Debug, Clone, Copy)]
+pub struct RetryConfig {
+    /// Number of retries after the first attempt
+    pub max_retries: u32,
+    /// Delay before the first retry. Doubled for every following retry
+    pub initial_backoff: Duration,
+    /// Upper bound of the delay between retries
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+        }
+    }
+}
+
+/// Status, headers and body of a response that has been read in full
+struct RawResponse {
+    url: Url,
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl RawResponse {
+    fn error_for_status(self) -> Result<Self, Error> {
+        if self.status.is_success() {
+            Ok(self)
+        } else {
+            Err(Error::Status {
+                url: self.url,
+                status: self.status,
+            })
+        }
+    }
+
+    fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T, Error> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+}
+
+/// Beacon API client for beacon states of the preset of `S` that can query headers, blocks and states.
+///
+/// Requests go to the endpoints in the order they were given. Transient failures are retried
+/// according to the [RetryConfig] before failing over to the next endpoint.
+///
+/// With a [StateCache] finalized states are read from and stored in the cache. All other
+/// responses are always requested from the endpoints.
+pub struct BeaconClient<S> {
     http: ClientWithMiddleware,
-    endpoint: Url,
+    endpoints: Vec<Url>,
+    retry: RetryConfig,
+    cache: Option<StateCache>,
+    preset: PhantomData<fn() -> S>,
 }
 
-impl BeaconClient {
-    /// Creates a new beacon endpoint API client.
-    pub fn new<U: IntoUrl>(endpoint: U) -> Result<Self, Error> {
-        let client = reqwest::Client::new();
-        Ok(Self {
-            http: client.into(),
-            endpoint: endpoint.into_url()?,
-        })
+impl<S: PresetBeaconState> BeaconClient<S> {
+    /// Creates a new beacon API client over the given endpoints.
+    pub fn new<U: IntoUrl>(endpoints: impl IntoIterator<Item = U>) -> Result<Self, Error> {
+        Self::with_http(reqwest::Client::new().into(), endpoints)
     }
 
-    /// Creates a new beacon endpoint API client with caching.
-    pub fn new_with_cache<U: IntoUrl>(endpoint: U, cache_dir: &str) -> Result<Self, Error> {
-        let client = reqwest::Client::new();
-        let manager = CACacheManager {
-            path: cache_dir.into(),
-        };
-        let cache = Cache(HttpCache {
-            mode: CacheMode::ForceCache,
-            manager,
-            options: HttpCacheOptions::default(),
-        });
-        let client_with_middleware = ClientBuilder::new(client).with(cache).build();
+    /// Creates a new beacon API client over the given endpoints that caches finalized states.
+    pub fn new_with_cache<U: IntoUrl>(
+        endpoints: impl IntoIterator<Item = U>,
+        cache: StateCache,
+    ) -> Result<Self, Error> {
+        let mut client = Self::new(endpoints)?;
+        client.cache = Some(cache);
+        Ok(client)
+    }
 
+    fn with_http<U: IntoUrl>(
+        http: ClientWithMiddleware,
+        endpoints: impl IntoIterator<Item = U>,
+    ) -> Result<Self, Error> {
+        let endpoints = endpoints
+            .into_iter()
+            .map(IntoUrl::into_url)
+            .collect::<Result<Vec<_>, _>>()?;
+        if endpoints.is_empty() {
+            return Err(Error::NoEndpoints);
+        }
         Ok(Self {
-            http: client_with_middleware,
-            endpoint: endpoint.into_url()?,
+            http,
+            endpoints,
+            retry: RetryConfig::default(),
+            cache: None,
+            preset: PhantomData,
         })
     }
 
-    async fn http_get<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T, Error> {
-        let target = self.endpoint.join(path)?;
-        let resp = self.http.get(target).send().await?;
-        let value = resp.error_for_status()?.json().await?;
-        Ok(value)
+    /// Endpoints in the order requests are sent to them.
+    pub fn endpoints(&self) -> &[Url] {
+        &self.endpoints
+    }
+
+    /// Replaces the default retry policy.
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Sends a GET request, failing over to the next endpoint when one is unavailable.
+    /// Returns the last response or error if no endpoint could serve the request.
+    async fn get(&self, path: &str, accept: Option<&str>) -> Result<RawResponse, Error> {
+        let mut last = Err(Error::NoEndpoints);
+        for endpoint in &self.endpoints {
+            let result = self.get_from(endpoint, path, accept).await;
+            let fail_over = match &result {
+                Ok(resp) => should_fail_over(resp.status),
+                Err(err) => err.is_transient(),
+            };
+            if !fail_over {
+                return result;
+            }
+            tracing::warn!(%endpoint, "beacon endpoint unavailable, trying the next one");
+            last = result;
+        }
+        last
+    }
+
+    /// Sends a GET request to a single endpoint, retrying transient failures with exponential backoff.
+    /// The body is read as part of each attempt so a download that times out is retried as well.
+    async fn get_from(
+        &self,
+        endpoint: &Url,
+        path: &str,
+        accept: Option<&str>,
+    ) -> Result<RawResponse, Error> {
+        let url = endpoint.join(path)?;
+        let mut backoff = self.retry.initial_backoff;
+        let mut attempt = 0;
+        loop {
+            let result = self.get_once(&url, accept).await;
+            let transient = match &result {
+                Ok(resp) => should_retry(resp.status),
+                Err(err) => err.is_transient(),
+            };
+            if !transient || attempt == self.retry.max_retries {
+                return result;
+            }
+            match &result {
+                Ok(resp) => {
+                    tracing::warn!(%url, status = %resp.status, attempt, "retrying beacon request")
+                }
+                Err(err) => tracing::warn!(%url, %err, attempt, "retrying beacon request"),
+            }
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(self.retry.max_backoff);
+            attempt += 1;
+        }
+    }
+
+    async fn get_once(&self, url: &Url, accept: Option<&str>) -> Result<RawResponse, Error> {
+        let mut request = self.http.get(url.clone());
+        if let Some(accept) = accept {
+            request = request.header(ACCEPT, accept);
+        }
+        let resp = request.send().await?;
+        Ok(RawResponse {
+            url: url.clone(),
+            status: resp.status(),
+            headers: resp.headers().clone(),
+            body: resp.bytes().await?,
+        })
     }
 
     /// Retrieves block details for given block id.
+    /// Fails with [Error::BlockNotFound] if the block id is a slot that was missed.
     #[tracing::instrument(skip(self), fields(block_id = %block_id))]
     pub async fn get_block_header(
         &self,
         block_id: impl Display,
     ) -> Result<SignedBeaconBlockHeader, Error> {
         let path = format!("eth/v1/beacon/headers/{block_id}");
-        let result: Response<GetBlockHeaderResponse> = self.http_get(&path).await?;
+        let resp = self.get(&path, None).await?;
+        if resp.status == StatusCode::NOT_FOUND {
+            return Err(Error::BlockNotFound(block_id.to_string()));
+        }
+        let result: Response<GetBlockHeaderResponse> = resp.error_for_status()?.json()?;
         Ok(result.data.header)
     }
 
-    /// Retrieves block hash for given beacon block id (e.g. slot).
+    /// Retrieves the root of the given block id (e.g. slot) from every endpoint and checks they agree.
+    /// Endpoints that cannot serve the block are skipped, but at least one of them must.
+    #[tracing::instrument(skip(self), fields(block_id = %block_id))]
+    pub async fn get_block_root(&self, block_id: impl Display) -> Result<Root, Error> {
+        let path = format!("eth/v1/beacon/blocks/{block_id}/root");
+        let mut agreed: Option<(&Url, Root)> = None;
+        for endpoint in &self.endpoints {
+            let resp = match self
+                .get_from(endpoint, &path, None)
+                .await
+                .and_then(RawResponse::error_for_status)
+            {
+                Ok(resp) => resp,
+                Err(err) => {
+                    tracing::warn!(%endpoint, %err, "skipping beacon endpoint in block root cross-check");
+                    continue;
+                }
+            };
+            let root = resp.json::<Response<GetBlockRootResponse>>()?.data.root;
+            match &agreed {
+                Some((first, expected)) if *expected != root => {
+                    return Err(Error::BlockRootMismatch {
+                        block_id: block_id.to_string(),
+                        first: (*first).clone(),
+                        other: endpoint.clone(),
+                    });
+                }
+                Some(_) => {}
+                None => agreed = Some((endpoint, root)),
+            }
+        }
+        agreed
+            .map(|(_, root)| root)
+            .ok_or_else(|| Error::BlockNotFound(block_id.to_string()))
+    }
+
+    /// Retrieves the execution payload of the beacon block with the given id (e.g. slot).
+    /// Fails if there is no block at that slot or the block predates the merge.
     #[tracing::instrument(skip(self), fields(block_id = %block_id))]
-    pub async fn get_eth1_block_hash_at_slot(&self, block_id: impl Display) -> Result<B256, Error> {
+    pub async fn get_execution_payload_header(
+        &self,
+        block_id: impl Display,
+    ) -> Result<ExecutionPayloadHeader, Error> {
         let path = format!("eth/v2/beacon/blocks/{block_id}");
-        let result: serde_json::Value = self.http_get(&path).await?;
-        let hash_str = result["data"]["message"]["body"]["eth1_data"]["block_hash"]
-            .as_str()
-            .unwrap();
-        Ok(B256::from_hex(&hash_str.trim_start_matches("0x")).unwrap())
+        let resp = self.get(&path, None).await?;
+        if resp.status == StatusCode::NOT_FOUND {
+            return Err(Error::BlockNotFound(block_id.to_string()));
+        }
+        let result: Response<ExecutionPayloadBlock> = resp.error_for_status()?.json()?;
+        match result.data.message.body.execution_payload {
+            Some(payload) if !payload.block_hash.is_zero() => Ok(payload),
+            _ => Err(Error::PreMerge(block_id.to_string())),
+        }
+    }
+
+    /// Retrieves the justified and finalized checkpoints of the given state id (e.g. `head`) from
+    /// a single endpoint.
+    async fn get_finality_checkpoints(
+        &self,
+        endpoint: &Url,
+        state_id: impl Display,
+    ) -> Result<GetFinalityCheckpointsResponse, Error> {
+        let path = format!("eth/v1/beacon/states/{state_id}/finality_checkpoints");
+        let result: Response<GetFinalityCheckpointsResponse> = self
+            .get_from(endpoint, &path, None)
+            .await?
+            .error_for_status()?
+            .json()?;
+        Ok(result.data)
+    }
+
+    /// Retrieves the first slot of the finalized epoch at the head of the chain.
+    /// Every endpoint is asked and the earliest finalized slot is returned, so a slot is only
+    /// considered final once all reachable endpoints have finalized it.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_finalized_slot(&self) -> Result<u64, Error> {
+        let mut finalized_slot: Option<u64> = None;
+        let mut last_err = Error::NoEndpoints;
+        for endpoint in &self.endpoints {
+            let checkpoints = match self.get_finality_checkpoints(endpoint, "head").await {
+                Ok(checkpoints) => checkpoints,
+                Err(err) => {
+                    tracing::warn!(%endpoint, %err, "skipping beacon endpoint in finality check");
+                    last_err = err;
+                    continue;
+                }
+            };
+            let slot = checkpoints.finalized.epoch * S::SLOTS_PER_EPOCH;
+            finalized_slot = Some(finalized_slot.map_or(slot, |earliest| earliest.min(slot)));
+        }
+        finalized_slot.ok_or(last_err)
     }
 
+    /// Retrieves the beacon state for the given state id (e.g. slot).
+    /// SSZ is requested as it is far smaller and faster to decode; JSON is only used if the node does not support it.
+    ///
+    /// With more than one endpoint the latest block of the state is checked against the block root
+    /// reported by every endpoint before the state is returned.
+    ///
+    /// States requested by slot are served from the cache if present. Downloaded states are
+    /// only cached once they are finalized.
     #[tracing::instrument(skip(self), fields(state_id = %state_id))]
-    pub async fn get_beacon_state(&self, state_id: impl Display) -> Result<BeaconState, Error> {
+    pub async fn get_beacon_state(&self, state_id: impl Display) -> Result<S, Error> {
+        let slot = state_id.to_string().parse::<u64>().ok();
+        if let (Some(cache), Some(slot)) = (&self.cache, slot) {
+            match cache.get(slot) {
+                Ok(Some(state)) => {
+                    tracing::debug!("beacon state served from the cache");
+                    return Ok(state);
+                }
+                Ok(None) => {}
+                Err(err) => tracing::warn!(%err, "could not read cached beacon state"),
+            }
+        }
+
         let path = format!("eth/v2/debug/beacon/states/{state_id}");
-        let result: VersionedResponse<BeaconState> = self.http_get(&path).await?;
-        if result.version.to_string() != result.inner.data.version().to_string() {
-            tracing::warn!(
-                "FORK: {:?}, Version mismatch: {} != {}",
-                result.inner.data.fork(),
-                result.version,
-                result.inner.data.version()
+        let accept = format!("{SSZ_CONTENT_TYPE};q=1.0,application/json;q=0.9");
+        let resp = self.get(&path, Some(&accept)).await?.error_for_status()?;
+
+        let is_ssz = resp
+            .headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with(SSZ_CONTENT_TYPE));
+        let (state, ssz) = if is_ssz {
+            let fork = consensus_version(&resp.headers)?;
+            let state = S::from_versioned_ssz(fork, &resp.body)?;
+            (state, Some(resp.body))
+        } else {
+            tracing::debug!("node did not return SSZ, falling back to JSON");
+            let result: VersionedResponse<serde_json::Value> = resp.json()?;
+            let state = S::from_versioned_json(result.version, result.inner.data)?;
+            (state, None)
+        };
+
+        if self.endpoints.len() > 1 {
+            self.cross_check_state(&state).await?;
+        }
+        if let Some(cache) = &self.cache {
+            if let Err(err) = self.cache_if_finalized(cache, &state, ssz).await {
+                tracing::warn!(%err, "could not cache beacon state");
+            }
+        }
+        Ok(state)
+    }
+
+    /// Stores the state in the cache if it is finalized. States past the finalized checkpoint,
+    /// including any `head` state, can still be reorged and are never cached.
+    async fn cache_if_finalized(
+        &self,
+        cache: &StateCache,
+        state: &S,
+        ssz: Option<Bytes>,
+    ) -> anyhow::Result<()> {
+        let finalized_slot = self.get_finalized_slot().await?;
+        if state.slot() > finalized_slot {
+            tracing::debug!(
+                finalized_slot,
+                "not caching beacon state that is not finalized"
             );
-            return Err(Error::VersionMismatch);
+            return Ok(());
         }
-        Ok(result.inner.data)
+        let ssz = match ssz {
+            Some(ssz) => ssz,
+            None => state.to_ssz()?.into(),
+        };
+        cache.put(state.slot(), state.version(), &ssz)
+    }
+
+    /// Checks the root of the latest block in the state against the root reported by every endpoint
+    async fn cross_check_state(&self, state: &S) -> Result<(), Error> {
+        let mut header = state.latest_block_header().clone();
+        // The state root of the latest block is only filled in when the next slot is processed
+        if header.state_root == Root::default() {
+            header.state_root = state.hash_tree_root()?;
+        }
+        let block_root = header.hash_tree_root()?;
+        let expected = self.get_block_root(header.slot).await?;
+        if block_root != expected {
+            return Err(Error::StateMismatch {
+                slot: header.slot,
+                block_root,
+                expected,
+            });
+        }
+        Ok(())
     }
 }
+
+/// Whether the request is worth repeating against the same endpoint
+fn should_retry(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Whether another endpoint may be able to serve the request. Many providers do not expose the
+/// debug state API or prune historical states, so missing routes and resources are tried elsewhere.
+fn should_fail_over(status: StatusCode) -> bool {
+    should_retry(status)
+        || status == StatusCode::NOT_FOUND
+        || status == StatusCode::METHOD_NOT_ALLOWED
+}
+
+/// Reads the fork from the `Eth-Consensus-Version` header
+fn consensus_version(headers: &HeaderMap) -> Result<Version, Error> {
+    let version = headers
+        .get(CONSENSUS_VERSION_HEADER)
+        .ok_or(Error::MissingConsensusVersion)?
+        .to_str()
+        .map_err(|_| Error::MissingConsensusVersion)?;
+    version
+        .to_lowercase()
+        .parse()
+        .map_err(|_| Error::UnsupportedConsensusVersion(version.to_string()))
+}
@@ -12,32 +12,35 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod beacon_cache;
 mod beacon_client;
+mod network;
+mod state_source;
 
 use alloy::{
-    dyn_abi::SolType, network::EthereumWallet, primitives::Address, providers::ProviderBuilder,
+    dyn_abi::SolType,
+    eips::BlockNumberOrTag,
+    network::EthereumWallet,
+    primitives::{Address, B256, U256},
+    providers::{Provider, ProviderBuilder},
     signers::local::PrivateKeySigner,
 };
-use anyhow::{Context, Result};
-use balance_and_exits_builder::{
-    MAINNET_ELF as BALANCE_AND_EXITS_ELF, MAINNET_ID as BALANCE_AND_EXITS_ID,
-};
-use beacon_client::BeaconClient;
+use anyhow::{ensure, Context, Result};
+use beacon_cache::{format_size, parse_size, CacheArgs};
+use beacon_state::PresetBeaconState;
 use clap::Parser;
-use ethereum_consensus::phase0::mainnet::{HistoricalBatch, SLOTS_PER_HISTORICAL_ROOT};
-use guest_io::{
-    mainnet::{WITHDRAWAL_CREDENTIALS, WITHDRAWAL_VAULT_ADDRESS},
-    ETH_SEPOLIA_CHAIN_SPEC,
-};
-use membership_builder::{
-    MAINNET_ELF as VALIDATOR_MEMBERSHIP_ELF, MAINNET_ID as VALIDATOR_MEMBERSHIP_ID,
-};
+use ethereum_consensus::ssz::prelude::HashTreeRoot;
+use guest_io::{ConsensusPreset, CredentialSet, ProvableBeaconState};
+use network::{CustomNetworkArgs, Network, NetworkDescriptor};
 use risc0_ethereum_contracts::encode_seal;
 use risc0_steel::{ethereum::EthEvmEnv, Account};
 use risc0_zkvm::{default_prover, ExecutorEnv, ProverOpts, Receipt, VerifierContext};
+use state_source::{BeaconSource, BeaconSourceArgs, BeaconStateSource};
 use std::{
     fs::{read, write},
+    num::NonZeroUsize,
     path::PathBuf,
+    sync::Mutex,
 };
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 use url::Url;
@@ -50,6 +53,15 @@ alloy::sol!(
         uint256 totalExitedValidators;
     }
 
+    struct AuxiliaryReport {
+        uint256 pendingDepositsGwei;
+        uint256 pendingPartialWithdrawalsGwei;
+        uint256 pendingConsolidationsFromLido;
+        uint256 pendingConsolidationsToLido;
+        uint256 slashedValidators;
+        uint256 slashedBalanceGwei;
+    }
+
     struct Commitment {
         uint256 id;
         bytes32 digest;
@@ -59,7 +71,7 @@ alloy::sol!(
     /// @title Receiver of oracle reports and proof data
     #[sol(rpc, all_derives)]
     interface IOracleProofReceiver {
-        function update(uint256 refSlot, Report calldata r, bytes calldata seal, Commitment calldata commitment) external;
+        function update(uint256 refSlot, uint256 blockSlot, uint256 childSlot, Report calldata r, AuxiliaryReport calldata aux, bytes32 stateRoot, uint8 fork, bytes calldata seal, Commitment calldata commitment) external;
     }
 );
 
@@ -72,9 +84,18 @@ alloy::sol!(
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 struct Args {
-    /// slot at which to base the proofs
-    #[clap(long)]
-    slot: u64,
+    /// Network to build proofs for. Selects the withdrawal credentials, vault address,
+    /// chain spec and guest programs.
+    #[clap(long, env = "ETH_NETWORK", value_enum, default_value_t = Network::Mainnet)]
+    network: Network,
+
+    #[clap(flatten)]
+    custom_network: CustomNetworkArgs,
+
+    /// Withdrawal credentials that define membership. Defaults to the Lido withdrawal credentials
+    /// of the selected network. Membership proofs for any other set cannot be aggregated.
+    #[clap(long, value_delimiter = ',')]
+    withdrawal_credentials: Vec<B256>,
 
     /// The top validator index proofs  will be extended to.
     /// If not included it will proceed up to the total number of validators
@@ -92,13 +113,21 @@ struct Args {
 enum Command {
     /// Generate a proof from a given input
     Prove {
-        /// Ethereum beacon node HTTP RPC endpoint.
-        #[clap(long, env)]
-        beacon_rpc_url: Url,
+        /// slot at which to base the proofs
+        #[clap(long)]
+        slot: u64,
+
+        #[clap(flatten)]
+        beacon_source: BeaconSourceArgs,
 
         #[clap(long = "out", short)]
         out_path: PathBuf,
 
+        /// Build proofs for a slot that is not finalized yet. Such a proof may be rooted in
+        /// a block that is later reorged out and so can not be verified against the chain.
+        #[clap(long)]
+        allow_unfinalized: bool,
+
         #[clap(subcommand)]
         command: ProveCommand,
     },
@@ -123,12 +152,42 @@ enum Command {
         #[clap(long = "proof", short)]
         proof_path: PathBuf,
     },
+    /// Inspect or prune the beacon state cache
+    Cache {
+        #[clap(flatten)]
+        cache: CacheArgs,
+
+        #[clap(subcommand)]
+        command: CacheCommand,
+    },
+}
+
+#[derive(Parser, Debug)]
+enum CacheCommand {
+    /// List the cached beacon states
+    Ls,
+    /// Evict the least recently used beacon states until the cache fits in its size budget
+    Prune {
+        /// Size budget to prune to instead of the configured one, e.g. `0` to clear the cache
+        #[clap(long, value_parser = parse_size)]
+        max_size: Option<u64>,
+
+        /// Also remove all states before this slot
+        #[clap(long)]
+        before_slot: Option<u64>,
+    },
 }
 
 #[derive(Parser, Debug)]
 enum ProveCommand {
     /// An initial membership proof
     Initial,
+    /// An initial membership proof built from chunks of validators proven in parallel and then merged
+    InitialChunked {
+        /// Number of validators proven by each chunk
+        #[clap(long)]
+        chunk_size: u64,
+    },
     /// A continuation from a prior membership proof
     ContinuationFrom { prior_path: PathBuf },
     /// An aggregation (oracle) proof that can be submitted on-chain
@@ -149,37 +208,97 @@ async fn main() -> Result<()> {
         .init();
 
     let args = Args::parse();
+    if let Command::Cache { cache, command } = args.command {
+        return run_cache(cache, command);
+    }
+    let network = args.network.descriptor(&args.custom_network)?;
+
+    // Beacon states are decoded with the list and vector lengths of the preset of the network
+    match network.config.preset {
+        ConsensusPreset::Mainnet => run::<beacon_state::mainnet::BeaconState>(args, &network).await,
+        ConsensusPreset::Minimal => run::<beacon_state::minimal::BeaconState>(args, &network).await,
+    }
+}
+
+async fn run<S: ProvableBeaconState>(args: Args, network: &NetworkDescriptor) -> Result<()> {
+    let withdrawal_credentials = if args.withdrawal_credentials.is_empty() {
+        network.config.credential_set()
+    } else {
+        CredentialSet::new(args.withdrawal_credentials)
+    };
 
     match args.command {
         Command::Prove {
             out_path,
             command: ProveCommand::Initial,
-            beacon_rpc_url,
+            slot,
+            beacon_source,
+            allow_unfinalized,
         } => {
-            let input =
-                build_membership_input(beacon_rpc_url, args.slot, args.max_validator_index, None)
-                    .await?;
+            let source = beacon_source.source::<S>()?;
+            ensure_finalized(&source, slot, allow_unfinalized).await?;
+            let block_slot = resolve_block_slot(&source, slot).await?;
+            let input = build_membership_input(
+                network,
+                withdrawal_credentials,
+                &source,
+                block_slot,
+                args.max_validator_index,
+                None,
+            )
+            .await?;
             let proof =
-                build_membership_proof(input, None, args.slot, args.max_validator_index).await?;
+                build_membership_proof(network, input, None, block_slot, args.max_validator_index)
+                    .await?;
+            write(out_path, &bincode::serialize(&proof)?)?;
+        }
+        Command::Prove {
+            out_path,
+            command: ProveCommand::InitialChunked { chunk_size },
+            slot,
+            beacon_source,
+            allow_unfinalized,
+        } => {
+            let source = beacon_source.source::<S>()?;
+            ensure_finalized(&source, slot, allow_unfinalized).await?;
+            let block_slot = resolve_block_slot(&source, slot).await?;
+            let proof = build_chunked_membership_proof(
+                network,
+                withdrawal_credentials,
+                &source,
+                block_slot,
+                args.max_validator_index,
+                chunk_size,
+            )
+            .await?;
             write(out_path, &bincode::serialize(&proof)?)?;
         }
         Command::Prove {
             out_path,
             command: ProveCommand::ContinuationFrom { prior_path },
-            beacon_rpc_url,
+            slot,
+            beacon_source,
+            allow_unfinalized,
         } => {
+            let source = beacon_source.source::<S>()?;
+            ensure_finalized(&source, slot, allow_unfinalized).await?;
+            let block_slot = resolve_block_slot(&source, slot).await?;
             let prior_proof: MembershipProof = bincode::deserialize(&read(prior_path)?)?;
+            prior_proof.ensure_matches(network.network, &withdrawal_credentials)?;
             let input = build_membership_input(
-                beacon_rpc_url,
-                args.slot,
+                network,
+                withdrawal_credentials,
+                &source,
+                block_slot,
                 args.max_validator_index,
                 Some(prior_proof.slot),
             )
             .await?;
             let proof = build_membership_proof(
+                network,
                 input,
                 Some(prior_proof),
-                args.slot,
+                block_slot,
                 args.max_validator_index,
             )
             .await?;
@@ -192,12 +311,26 @@ async fn main() -> Result<()> {
                     membership_proof_path,
                     eth_rpc_url,
                 },
-            beacon_rpc_url,
+            slot,
+            beacon_source,
+            allow_unfinalized,
         } => {
-            let input = build_aggregate_input(beacon_rpc_url, args.slot, eth_rpc_url).await?;
+            let source = beacon_source.source::<S>()?;
+            ensure_finalized(&source, slot, allow_unfinalized).await?;
+            let block_slot = resolve_block_slot(&source, slot).await?;
             let membership_proof: MembershipProof =
                 bincode::deserialize(&read(membership_proof_path)?)?;
-            let proof = build_aggregate_proof(input, membership_proof, args.slot).await?;
+            membership_proof.ensure_matches(network.network, &network.config.credential_set())?;
+            ensure!(
+                membership_proof.slot == block_slot,
+                "membership proof is for slot {} but the report for slot {} is based on the block at slot {}",
+                membership_proof.slot,
+                slot,
+                block_slot
+            );
+            let (input, resolution) =
+                build_aggregate_input(network, &source, slot, block_slot, eth_rpc_url).await?;
+            let proof = build_aggregate_proof(network, input, membership_proof, resolution).await?;
             write(out_path, &bincode::serialize(&proof)?)?;
         }
         Command::Submit {
@@ -208,6 +341,7 @@ async fn main() -> Result<()> {
             proof_path,
         } => {
             submit_aggregate_proof(
+                network,
                 eth_wallet_private_key,
                 eth_rpc_url,
                 contract,
@@ -216,51 +350,166 @@ async fn main() -> Result<()> {
             )
             .await?
         }
+        Command::Cache { .. } => {
+            unreachable!("cache commands are run before the network is resolved")
+        }
     }
 
     Ok(())
 }
 
+/// Runs a cache command. The cache holds states of any network, so no network is resolved for it.
+fn run_cache(cache: CacheArgs, command: CacheCommand) -> Result<()> {
+    let cache = cache.cache()?;
+    match command {
+        CacheCommand::Ls => {
+            let entries = cache.entries()?;
+            for entry in &entries {
+                println!(
+                    "{}\t{}\t{}",
+                    entry.slot,
+                    entry.fork,
+                    format_size(entry.size)
+                );
+            }
+            println!(
+                "{} states, {} of {}",
+                entries.len(),
+                format_size(entries.iter().map(|entry| entry.size).sum()),
+                format_size(cache.max_size())
+            );
+        }
+        CacheCommand::Prune {
+            max_size,
+            before_slot,
+        } => {
+            let mut removed = match before_slot {
+                Some(slot) => cache.remove_before(slot)?,
+                None => Vec::new(),
+            };
+            removed.extend(cache.evict(max_size.unwrap_or(cache.max_size()))?);
+            println!(
+                "removed {} states, freed {}",
+                removed.len(),
+                format_size(removed.iter().map(|entry| entry.size).sum())
+            );
+        }
+    }
+    Ok(())
+}
+
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 struct MembershipProof {
+    network: Network,
+    withdrawal_credentials: CredentialSet,
     slot: u64,
     max_validator_index: u64,
     receipt: Receipt,
 }
 
 impl MembershipProof {
-    pub fn new(slot: u64, max_validator_index: u64, receipt: Receipt) -> Self {
+    pub fn new(
+        network: Network,
+        withdrawal_credentials: CredentialSet,
+        slot: u64,
+        max_validator_index: u64,
+        receipt: Receipt,
+    ) -> Self {
         Self {
+            network,
+            withdrawal_credentials,
             slot,
             max_validator_index,
             receipt,
         }
     }
+
+    fn ensure_matches(
+        &self,
+        network: Network,
+        withdrawal_credentials: &CredentialSet,
+    ) -> Result<()> {
+        ensure!(
+            self.network == network,
+            "membership proof was built for {} but {} was selected",
+            self.network,
+            network
+        );
+        ensure!(
+            &self.withdrawal_credentials == withdrawal_credentials,
+            "membership proof was built for a different set of withdrawal credentials"
+        );
+        Ok(())
+    }
 }
 
-#[tracing::instrument(skip(beacon_rpc_url))]
+/// Refuses to build proofs for a slot the beacon nodes have not finalized unless explicitly allowed
+async fn ensure_finalized<S: ProvableBeaconState>(
+    source: &BeaconSource<S>,
+    slot: u64,
+    allow_unfinalized: bool,
+) -> Result<()> {
+    match source.finalized_slot().await? {
+        Some(finalized_slot) if slot > finalized_slot => {
+            ensure!(
+                allow_unfinalized,
+                "slot {slot} is not finalized yet (finalized up to slot {finalized_slot}), \
+                pass --allow-unfinalized to prove it anyway"
+            );
+            tracing::warn!(
+                "Proving slot {} which is not finalized yet (finalized up to slot {}). \
+                The proof may not be verifiable if the block is reorged out",
+                slot,
+                finalized_slot
+            );
+        }
+        Some(_) => {}
+        None => tracing::warn!(
+            "Cannot check the finality of slot {} when reading archived beacon states",
+            slot
+        ),
+    }
+    Ok(())
+}
+
+/// Resolves the block the report for the reference slot is based on. Following LIP-23 the report
+/// for a missed slot is based on the state of the last block before it.
+async fn resolve_block_slot(source: &impl BeaconStateSource, ref_slot: u64) -> Result<u64> {
+    let block_slot = source.resolve_block_slot(ref_slot).await?;
+    if block_slot != ref_slot {
+        tracing::warn!(
+            "Slot {} was missed, proving the last block before it at slot {}",
+            ref_slot,
+            block_slot
+        );
+    }
+    Ok(block_slot)
+}
+
+#[tracing::instrument(skip(network, withdrawal_credentials, source))]
 async fn build_membership_input<'a>(
-    beacon_rpc_url: Url,
+    network: &NetworkDescriptor,
+    withdrawal_credentials: CredentialSet,
+    source: &impl BeaconStateSource,
     slot: u64,
     max_validator_index: Option<u64>,
     prior_slot: Option<u64>,
 ) -> Result<guest_io::validator_membership::Input<'a>> {
     use guest_io::validator_membership::Input;
 
-    let beacon_client = BeaconClient::new_with_cache(beacon_rpc_url, "./beacon-cache")?;
-
     tracing::info!("Retrieving beacon state...");
-    let beacon_state = beacon_client.get_beacon_state(slot).await?;
+    let beacon_state = source.get_beacon_state(slot).await?;
 
     tracing::info!("Total validators: {}", beacon_state.validators().len());
     tracing::info!(
-        "Lido validators: {}",
+        "Member validators: {}",
         beacon_state
             .validators()
             .iter()
-            .filter(
-                |validator| validator.withdrawal_credentials.as_slice() == WITHDRAWAL_CREDENTIALS
-            )
+            .filter(|validator| withdrawal_credentials.matches(
+                validator.withdrawal_credentials.as_slice(),
+                &network.config.withdrawal_credential_prefixes
+            ))
             .count()
     );
 
@@ -268,46 +517,51 @@ async fn build_membership_input<'a>(
         max_validator_index.unwrap_or((beacon_state.validators().len() - 1) as u64);
 
     let input = if let Some(prior_slot) = prior_slot {
-        let hist_summary = if beacon_state.slot() > prior_slot + (SLOTS_PER_HISTORICAL_ROOT as u64)
-        {
+        let slots_per_historical_root = network.config.slots_per_historical_root;
+        let hist_summary = if beacon_state.slot() > prior_slot + slots_per_historical_root {
             // this is a long range continuation and we need to provide an intermediate historical summary
             tracing::info!("Long range continuation detected");
-            let inter_slot = (prior_slot / (SLOTS_PER_HISTORICAL_ROOT as u64) + 1)
-                * (SLOTS_PER_HISTORICAL_ROOT as u64);
+            let inter_slot =
+                (prior_slot / slots_per_historical_root + 1) * slots_per_historical_root;
             tracing::info!("Fetching intermediate state at slot: {}", inter_slot);
-            let inter_state = beacon_client.get_beacon_state(inter_slot).await?;
-            Some(HistoricalBatch {
-                block_roots: inter_state.block_roots().clone(),
-                state_roots: inter_state.state_roots().clone(),
-            })
+            let inter_state = source.get_beacon_state(inter_slot).await?;
+            Some(inter_state.historical_batch())
         } else {
             None
         };
 
         tracing::info!("Retrieving intermediate beacon state...");
-        let prior_beacon_state = beacon_client.get_beacon_state(prior_slot).await?;
+        let prior_beacon_state = source.get_beacon_state(prior_slot).await?;
         let prior_max_validator_index = (prior_beacon_state.validators().len() - 1) as u64;
 
         tracing::info!("Building input. This may take a few minutes...");
         Input::build_continuation(
-            WITHDRAWAL_CREDENTIALS,
+            &network.config,
+            withdrawal_credentials,
             &prior_beacon_state,
             prior_max_validator_index,
             &beacon_state,
             max_validator_index,
             hist_summary,
-            VALIDATOR_MEMBERSHIP_ID,
+            network.membership.id,
         )?
     } else {
         tracing::info!("Building input. This may take a few minutes...");
 
-        Input::build_initial(beacon_state, max_validator_index, VALIDATOR_MEMBERSHIP_ID)?
+        Input::build_initial(
+            &network.config,
+            withdrawal_credentials,
+            beacon_state,
+            max_validator_index,
+            network.membership.id,
+        )?
     };
     Ok(input)
 }
 
-#[tracing::instrument(skip(input, prior_proof))]
+#[tracing::instrument(skip(network, input, prior_proof))]
 async fn build_membership_proof<'a>(
+    network: &NetworkDescriptor,
     input: guest_io::validator_membership::Input<'a>,
     prior_proof: Option<MembershipProof>,
     slot: u64,
@@ -329,65 +583,242 @@ async fn build_membership_proof<'a>(
     let session_info = default_prover().prove_with_ctx(
         env,
         &VerifierContext::default(),
-        VALIDATOR_MEMBERSHIP_ELF,
+        &network.membership.elf,
         &ProverOpts::succinct(),
     )?;
     tracing::info!("total cycles: {}", session_info.stats.total_cycles);
 
-    let proof = MembershipProof::new(slot, input.input.max_validator_index, session_info.receipt);
+    let proof = MembershipProof::new(
+        network.network,
+        input.input.withdrawal_credentials.clone(),
+        slot,
+        input.input.max_validator_index,
+        session_info.receipt,
+    );
 
     Ok(proof)
 }
 
+/// Proves membership of fixed size chunks of validators in parallel and merges the chunk receipts
+/// into a single membership proof
+#[tracing::instrument(skip(network, withdrawal_credentials, source))]
+async fn build_chunked_membership_proof(
+    network: &NetworkDescriptor,
+    withdrawal_credentials: CredentialSet,
+    source: &impl BeaconStateSource,
+    slot: u64,
+    max_validator_index: Option<u64>,
+    chunk_size: u64,
+) -> Result<MembershipProof> {
+    use guest_io::validator_membership::{ChunkJournal, Input};
+
+    ensure!(chunk_size > 0, "chunk size must be greater than zero");
+
+    tracing::info!("Retrieving beacon state...");
+    let beacon_state = source.get_beacon_state(slot).await?;
+
+    let max_validator_index =
+        max_validator_index.unwrap_or((beacon_state.validators().len() - 1) as u64);
+    let ranges: Vec<(u64, u64)> = (0..=max_validator_index)
+        .step_by(chunk_size.try_into()?)
+        .map(|start| (start, (start + chunk_size - 1).min(max_validator_index)))
+        .collect();
+
+    let inputs = Input::build_chunks(
+        &network.config,
+        withdrawal_credentials.clone(),
+        &beacon_state,
+        &ranges,
+        network.membership.id,
+    )?;
+    drop(beacon_state);
+
+    // proving is CPU bound, so run at most one chunk prover per core
+    let workers = std::thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(inputs.len());
+    tracing::info!(
+        "Generating {} chunk proofs of up to {} validators on {} workers...",
+        inputs.len(),
+        chunk_size,
+        workers
+    );
+    let queue = Mutex::new(inputs.into_iter().enumerate());
+    let mut chunks = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| -> Result<Vec<(usize, (ChunkJournal, Receipt))>> {
+                    let mut proven = Vec::new();
+                    loop {
+                        let Some((index, input)) = queue.lock().unwrap().next() else {
+                            return Ok(proven);
+                        };
+                        let env = ExecutorEnv::builder()
+                            .write_frame(&bincode::serialize(&input.without_receipt())?)
+                            .build()?;
+                        let session_info = default_prover().prove_with_ctx(
+                            env,
+                            &VerifierContext::default(),
+                            &network.membership.elf,
+                            &ProverOpts::succinct(),
+                        )?;
+                        let (start, end) = ranges[index];
+                        tracing::info!(
+                            "chunk {}..={} total cycles: {}",
+                            start,
+                            end,
+                            session_info.stats.total_cycles
+                        );
+                        let journal =
+                            ChunkJournal::from_bytes(&session_info.receipt.journal.bytes)?;
+                        proven.push((index, (journal, session_info.receipt)));
+                    }
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("chunk prover panicked"))
+            .collect::<Result<Vec<_>>>()
+    })?
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
+    chunks.sort_by_key(|(index, _)| *index);
+    let chunks = chunks.into_iter().map(|(_, chunk)| chunk).collect();
+
+    let input = Input::build_merge(withdrawal_credentials, chunks, network.membership.id)?;
+
+    tracing::info!("Merging chunk proofs...");
+    build_membership_proof(network, input, None, slot, Some(max_validator_index)).await
+}
+
+/// Blocks a report is tied to. The oracle contract reads the root of the proven block from
+/// the beacon roots of its child, so neither the reference slot nor the slot after it needs
+/// to contain a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+struct SlotResolution {
+    /// Reference slot the report is for
+    ref_slot: u64,
+    /// Slot of the last block at or before the reference slot, whose state is proven
+    block_slot: u64,
+    /// Slot of the first block after the reference slot, whose parent is the proven block
+    child_slot: u64,
+}
+
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 struct AggregateProof {
-    slot: u64,
+    network: Network,
+    resolution: SlotResolution,
     receipt: Receipt,
 }
 
-#[tracing::instrument(skip(beacon_rpc_url, eth_rpc_url))]
-async fn build_aggregate_input<'a>(
-    beacon_rpc_url: Url,
-    slot: u64,
+#[tracing::instrument(skip(network, source, eth_rpc_url))]
+async fn build_aggregate_input<'a, S: ProvableBeaconState>(
+    network: &NetworkDescriptor,
+    source: &BeaconSource<S>,
+    ref_slot: u64,
+    block_slot: u64,
     eth_rpc_url: Url,
-) -> Result<guest_io::balance_and_exits::Input<'a>> {
-    let beacon_client = BeaconClient::new_with_cache(beacon_rpc_url.clone(), "./beacon-cache")?;
-    let beacon_block_header = beacon_client.get_block_header(slot).await?;
+) -> Result<(guest_io::balance_and_exits::Input<'a>, SlotResolution)> {
+    let beacon_block_header = source.get_block_header(block_slot).await?;
 
-    let beacon_state = beacon_client.get_beacon_state(slot).await?;
+    let beacon_state = source.get_beacon_state(block_slot).await?;
 
-    let block_hash = beacon_client.get_eth1_block_hash_at_slot(slot).await?;
+    // Steel must read the withdrawal vault at the execution payload of the proven beacon block
+    let execution_payload = source.get_execution_payload_header(block_slot).await?;
+    tracing::info!(
+        "Reading the withdrawal vault at execution block {} ({})",
+        execution_payload.block_number,
+        execution_payload.block_hash
+    );
 
-    let mut env = EthEvmEnv::builder()
-        .chain_spec(&ETH_SEPOLIA_CHAIN_SPEC)
-        .rpc(eth_rpc_url)
-        .beacon_api(beacon_rpc_url)
-        .block_hash(block_hash)
-        .build()
-        .await?;
+    let builder = EthEvmEnv::builder()
+        .chain_spec(&network.config.chain_spec)
+        .rpc(eth_rpc_url.clone())
+        .block_hash(execution_payload.block_hash);
+
+    // A beacon API changes the type of the builder and its env, so the preflight is expanded for
+    // either env instead of being written out twice
+    macro_rules! preflight_withdrawal_vault {
+        ($builder:expr) => {{
+            let mut env = $builder.build().await?;
+            Account::preflight(network.config.withdrawal_vault_address, &mut env)
+                .bytecode(true)
+                .info()
+                .await?;
+            env.into_input().await?
+        }};
+    }
 
-    let _preflight_info = {
-        let account = Account::preflight(WITHDRAWAL_VAULT_ADDRESS, &mut env);
-        account.bytecode(true).info().await.unwrap()
+    // Archived states come without a beacon API, so the execution block is committed directly
+    let evm_input = match source.beacon_api_url() {
+        Some(beacon_api_url) => preflight_withdrawal_vault!(builder.beacon_api(beacon_api_url)),
+        None => preflight_withdrawal_vault!(builder),
     };
 
-    let evm_input = env.into_input().await?;
-
     let input = guest_io::balance_and_exits::Input::build(
-        WITHDRAWAL_CREDENTIALS,
-        &beacon_block_header.message,
+        &network.config,
+        &beacon_block_header,
         &beacon_state,
         evm_input,
     )?;
 
-    Ok(input)
+    let block_root = B256::from_slice(beacon_block_header.hash_tree_root()?.as_ref());
+    let child_slot = resolve_child_slot(
+        network,
+        eth_rpc_url,
+        execution_payload.block_number,
+        block_root,
+    )
+    .await?;
+    ensure!(
+        block_slot <= ref_slot && ref_slot < child_slot,
+        "block at slot {block_slot} is not the last block at or before slot {ref_slot}, \
+        the next block is at slot {child_slot}"
+    );
+    let resolution = SlotResolution {
+        ref_slot,
+        block_slot,
+        child_slot,
+    };
+    tracing::info!("Resolved report slots: {:?}", resolution);
+
+    Ok((input, resolution))
 }
 
-#[tracing::instrument(skip(input, membership_proof))]
+/// Slot of the child of the proven block. Its payload is the execution block after the payload of
+/// the proven block, and carries the root of the proven block as parent beacon block root.
+async fn resolve_child_slot(
+    network: &NetworkDescriptor,
+    eth_rpc_url: Url,
+    execution_block_number: u64,
+    block_root: B256,
+) -> Result<u64> {
+    let provider = ProviderBuilder::new().connect_http(eth_rpc_url);
+    let child_number = execution_block_number + 1;
+    let child = provider
+        .get_block_by_number(BlockNumberOrTag::Number(child_number))
+        .await?
+        .with_context(|| {
+            format!(
+                "execution block {child_number} after the proven block has not been produced yet"
+            )
+        })?;
+    ensure!(
+        child.header.parent_beacon_block_root == Some(block_root),
+        "execution block {child_number} is not the payload of a child of beacon block {block_root}"
+    );
+    let config = &network.config;
+    Ok((child.header.timestamp - config.genesis_time) / config.seconds_per_slot)
+}
+
+#[tracing::instrument(skip(network, input, membership_proof))]
 async fn build_aggregate_proof<'a>(
+    network: &NetworkDescriptor,
     input: guest_io::balance_and_exits::Input<'a>,
     membership_proof: MembershipProof,
-    slot: u64,
+    resolution: SlotResolution,
 ) -> Result<AggregateProof> {
     let input = input.with_receipt(membership_proof.receipt);
 
@@ -399,18 +830,20 @@ async fn build_aggregate_proof<'a>(
     let session_info = default_prover().prove_with_ctx(
         env,
         &VerifierContext::default(),
-        BALANCE_AND_EXITS_ELF,
+        &network.balance_and_exits.elf,
         &ProverOpts::groth16(),
     )?;
     tracing::info!("total cycles: {}", session_info.stats.total_cycles);
 
     Ok(AggregateProof {
-        slot,
+        network: network.network,
+        resolution,
         receipt: session_info.receipt,
     })
 }
 
 async fn submit_aggregate_proof(
+    network: &NetworkDescriptor,
     eth_wallet_private_key: PrivateKeySigner,
     eth_rpc_url: Url,
     contract: Option<Address>,
@@ -423,17 +856,35 @@ async fn submit_aggregate_proof(
         .connect_http(eth_rpc_url);
 
     let proof: AggregateProof = bincode::deserialize(&read(in_path)?)?;
+    ensure!(
+        proof.network == network.network,
+        "aggregate proof was built for {} but {} was selected",
+        proof.network,
+        network.network
+    );
     tracing::info!("verifying locally for sanity check");
-    proof.receipt.verify(BALANCE_AND_EXITS_ID)?;
+    proof.receipt.verify(network.balance_and_exits.id)?;
     tracing::info!("Local verification passed :)");
 
     let seal = encode_seal(&proof.receipt).context("encoding seal")?;
+    let journal = guest_io::balance_and_exits::Journal::abi_decode(&proof.receipt.journal.bytes)?;
+    let resolution = proof.resolution;
+    ensure!(
+        journal.slot == U256::from(resolution.block_slot),
+        "aggregate proof journal is for slot {} but the proof file claims slot {}",
+        journal.slot,
+        resolution.block_slot
+    );
 
     if let Some(test_contract) = test_contract {
         let contract = ITestVerifier::new(test_contract, provider.clone());
-        let block_root = proof.receipt.journal.bytes[..32].try_into()?;
-        let report = TestReport::abi_decode(&proof.receipt.journal.bytes[32..])?;
-        let call_builder = contract.verify(block_root, report, seal.clone().into());
+        let report = TestReport {
+            clBalanceGwei: journal.clBalanceGwei,
+            withdrawalVaultBalanceWei: journal.withdrawalVaultBalanceWei,
+            totalDepositedValidators: journal.totalDepositedValidators,
+            totalExitedValidators: journal.totalExitedValidators,
+        };
+        let call_builder = contract.verify(journal.blockRoot, report, seal.clone().into());
         let pending_tx = call_builder.send().await?;
         tracing::info!(
             "test_verifier: Submitted proof with tx hash: {}",
@@ -445,12 +896,33 @@ async fn submit_aggregate_proof(
 
     if let Some(contract) = contract {
         let contract = IOracleProofReceiver::new(contract, provider.clone());
-        // skip the first 32 bytes of the journal as that is the beacon block hash which is not part of the report
-        let report = Report::abi_decode(&proof.receipt.journal.bytes[32..])?;
-        let commitment = Commitment::abi_decode(&proof.receipt.journal.bytes[32 + 32..])?;
+        let report = Report {
+            clBalanceGwei: journal.clBalanceGwei,
+            withdrawalVaultBalanceWei: journal.withdrawalVaultBalanceWei,
+            totalDepositedValidators: journal.totalDepositedValidators,
+            totalExitedValidators: journal.totalExitedValidators,
+        };
+        let aux = AuxiliaryReport {
+            pendingDepositsGwei: journal.auxiliary.pendingDepositsGwei,
+            pendingPartialWithdrawalsGwei: journal.auxiliary.pendingPartialWithdrawalsGwei,
+            pendingConsolidationsFromLido: journal.auxiliary.pendingConsolidationsFromLido,
+            pendingConsolidationsToLido: journal.auxiliary.pendingConsolidationsToLido,
+            slashedValidators: journal.auxiliary.slashedValidators,
+            slashedBalanceGwei: journal.auxiliary.slashedBalanceGwei,
+        };
+        let commitment = Commitment {
+            id: journal.commitment.id,
+            digest: journal.commitment.digest,
+            configID: journal.commitment.configID,
+        };
         let call_builder = contract.update(
-            proof.slot.try_into()?,
+            U256::from(resolution.ref_slot),
+            U256::from(resolution.block_slot),
+            U256::from(resolution.child_slot),
             report,
+            aux,
+            journal.stateRoot,
+            journal.fork,
             seal.clone().into(),
             commitment,
         );
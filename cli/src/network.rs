@@ -0,0 +1,168 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{bail, Context, Result};
+use guest_io::{network, NetworkConfig};
+use risc0_zkvm::{compute_image_id, sha::Digest};
+use std::{
+    borrow::Cow,
+    fmt::Display,
+    fs::read,
+    path::{Path, PathBuf},
+};
+
+/// Networks the CLI can build and submit proofs for
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum, serde::Serialize, serde::Deserialize,
+)]
+pub enum Network {
+    Mainnet,
+    Sepolia,
+    Holesky,
+    Hoodi,
+    Custom,
+}
+
+impl Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Network::Mainnet => "mainnet",
+            Network::Sepolia => "sepolia",
+            Network::Holesky => "holesky",
+            Network::Hoodi => "hoodi",
+            Network::Custom => "custom",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A guest program compiled for a specific network
+pub struct GuestImage {
+    pub elf: Cow<'static, [u8]>,
+    pub id: Digest,
+}
+
+impl GuestImage {
+    fn builtin(elf: &'static [u8], id: [u32; 8]) -> Self {
+        Self {
+            elf: Cow::Borrowed(elf),
+            id: Digest::new(id),
+        }
+    }
+
+    /// Loads a guest program ELF from disk and computes its image ID
+    fn load(path: &Path) -> Result<Self> {
+        let elf = read(path).with_context(|| format!("reading guest ELF {}", path.display()))?;
+        let id = compute_image_id(&elf)?;
+        Ok(Self {
+            elf: Cow::Owned(elf),
+            id,
+        })
+    }
+}
+
+/// Everything that differs between networks. All network specific values used by the CLI
+/// must come from here so that inputs, guest images and chain specs can never be mixed up.
+pub struct NetworkDescriptor {
+    pub network: Network,
+    pub config: NetworkConfig,
+    pub membership: GuestImage,
+    pub balance_and_exits: GuestImage,
+}
+
+/// Options describing a network that is not built into the CLI. Only used with `--network custom`.
+#[derive(clap::Args, Debug)]
+pub struct CustomNetworkArgs {
+    /// TOML or JSON network config file
+    #[clap(long, env)]
+    network_config: Option<PathBuf>,
+
+    /// Membership guest ELF built for the custom network
+    #[clap(long, env)]
+    membership_elf: Option<PathBuf>,
+
+    /// Balance and exits guest ELF built for the custom network
+    #[clap(long, env)]
+    balance_and_exits_elf: Option<PathBuf>,
+}
+
+impl Network {
+    pub fn descriptor(self, custom: &CustomNetworkArgs) -> Result<NetworkDescriptor> {
+        match self {
+            Network::Mainnet => Ok(NetworkDescriptor {
+                network: self,
+                config: network::MAINNET.clone(),
+                membership: GuestImage::builtin(
+                    membership_builder::MAINNET_ELF,
+                    membership_builder::MAINNET_ID,
+                ),
+                balance_and_exits: GuestImage::builtin(
+                    balance_and_exits_builder::MAINNET_ELF,
+                    balance_and_exits_builder::MAINNET_ID,
+                ),
+            }),
+            Network::Sepolia => Ok(NetworkDescriptor {
+                network: self,
+                config: network::SEPOLIA.clone(),
+                membership: GuestImage::builtin(
+                    membership_builder::SEPOLIA_ELF,
+                    membership_builder::SEPOLIA_ID,
+                ),
+                balance_and_exits: GuestImage::builtin(
+                    balance_and_exits_builder::SEPOLIA_ELF,
+                    balance_and_exits_builder::SEPOLIA_ID,
+                ),
+            }),
+            Network::Holesky => Ok(NetworkDescriptor {
+                network: self,
+                config: network::HOLESKY.clone(),
+                membership: GuestImage::builtin(
+                    membership_builder::HOLESKY_ELF,
+                    membership_builder::HOLESKY_ID,
+                ),
+                balance_and_exits: GuestImage::builtin(
+                    balance_and_exits_builder::HOLESKY_ELF,
+                    balance_and_exits_builder::HOLESKY_ID,
+                ),
+            }),
+            Network::Hoodi => Ok(NetworkDescriptor {
+                network: self,
+                config: network::HOODI.clone(),
+                membership: GuestImage::builtin(
+                    membership_builder::HOODI_ELF,
+                    membership_builder::HOODI_ID,
+                ),
+                balance_and_exits: GuestImage::builtin(
+                    balance_and_exits_builder::HOODI_ELF,
+                    balance_and_exits_builder::HOODI_ID,
+                ),
+            }),
+            Network::Custom => {
+                let (Some(config), Some(membership_elf), Some(balance_and_exits_elf)) = (
+                    &custom.network_config,
+                    &custom.membership_elf,
+                    &custom.balance_and_exits_elf,
+                ) else {
+                    bail!("--network custom requires --network-config, --membership-elf and --balance-and-exits-elf");
+                };
+                Ok(NetworkDescriptor {
+                    network: self,
+                    config: NetworkConfig::load(config)?,
+                    membership: GuestImage::load(membership_elf)?,
+                    balance_and_exits: GuestImage::load(balance_and_exits_elf)?,
+                })
+            }
+        }
+    }
+}
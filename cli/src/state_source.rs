@@ -0,0 +1,449 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    beacon_cache::CacheArgs,
+    beacon_client::{self, BeaconClient, ExecutionPayloadHeader, RetryConfig},
+};
+use alloy_primitives::B256;
+use anyhow::{bail, ensure, Context, Result};
+use beacon_state::{PresetBeaconState, Version};
+use ethereum_consensus::{phase0::BeaconBlockHeader, primitives::Root, ssz::prelude::*};
+use guest_io::ProvableBeaconState;
+use std::{
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use url::Url;
+
+/// Missed slots that are searched for a block before giving up
+const MAX_MISSED_SLOTS: u64 = 64;
+
+/// Provides the beacon chain data the guest inputs are built from
+pub trait BeaconStateSource {
+    /// Beacon state of the preset of the network
+    type BeaconState: ProvableBeaconState;
+
+    /// Slot of the last block at or before the given slot. This is the slot itself unless it was missed.
+    ///
+    /// By default this is read from the latest block header of the state at the given slot.
+    async fn resolve_block_slot(&self, slot: u64) -> Result<u64> {
+        Ok(self
+            .get_beacon_state(slot)
+            .await?
+            .latest_block_header()
+            .slot)
+    }
+
+    /// Beacon state at the given slot
+    async fn get_beacon_state(&self, slot: u64) -> Result<Self::BeaconState>;
+
+    /// Header of the beacon block proposed at the given slot
+    async fn get_block_header(&self, slot: u64) -> Result<BeaconBlockHeader>;
+
+    /// Execution payload of the beacon block proposed at the given slot
+    async fn get_execution_payload_header(&self, slot: u64) -> Result<ExecutionPayloadHeader>;
+}
+
+impl<S: ProvableBeaconState> BeaconStateSource for BeaconClient<S> {
+    type BeaconState = S;
+
+    /// Looks for the block through the headers API instead of downloading the state
+    async fn resolve_block_slot(&self, slot: u64) -> Result<u64> {
+        for candidate in (slot.saturating_sub(MAX_MISSED_SLOTS)..=slot).rev() {
+            match BeaconClient::get_block_header(self, candidate).await {
+                Ok(header) => return Ok(header.message.slot),
+                Err(beacon_client::Error::BlockNotFound(_)) => {
+                    tracing::info!("Slot {} was missed", candidate)
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+        bail!("no beacon block in the {MAX_MISSED_SLOTS} slots up to slot {slot}")
+    }
+
+    async fn get_beacon_state(&self, slot: u64) -> Result<S> {
+        Ok(BeaconClient::get_beacon_state(self, slot).await?)
+    }
+
+    async fn get_block_header(&self, slot: u64) -> Result<BeaconBlockHeader> {
+        Ok(BeaconClient::get_block_header(self, slot).await?.message)
+    }
+
+    async fn get_execution_payload_header(&self, slot: u64) -> Result<ExecutionPayloadHeader> {
+        Ok(BeaconClient::get_execution_payload_header(self, slot).await?)
+    }
+}
+
+/// Beacon states archived as SSZ files named `<slot>.<fork>.ssz` (e.g. `11649024.electra.ssz`)
+/// in a single directory. Block headers and execution payloads are read from the state at the
+/// same slot, so they are only available for slots that have a block.
+pub struct StateDirectory<S> {
+    path: PathBuf,
+    preset: PhantomData<fn() -> S>,
+}
+
+impl<S> StateDirectory<S> {
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        ensure!(path.is_dir(), "{} is not a directory", path.display());
+        Ok(Self {
+            path,
+            preset: PhantomData,
+        })
+    }
+
+    fn state_path(&self, slot: u64) -> Result<PathBuf> {
+        let prefix = format!("{slot}.");
+        for entry in std::fs::read_dir(&self.path)? {
+            let path = entry?.path();
+            let is_match = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(&prefix) && name.ends_with(".ssz"));
+            if is_match {
+                return Ok(path);
+            }
+        }
+        bail!("no beacon state for slot {slot} in {}", self.path.display())
+    }
+}
+
+impl<S: ProvableBeaconState> BeaconStateSource for StateDirectory<S> {
+    type BeaconState = S;
+
+    async fn get_beacon_state(&self, slot: u64) -> Result<S> {
+        read_state_file(&self.state_path(slot)?).await
+    }
+
+    async fn get_block_header(&self, slot: u64) -> Result<BeaconBlockHeader> {
+        block_header_from_state(&self.get_beacon_state(slot).await?, slot)
+    }
+
+    async fn get_execution_payload_header(&self, slot: u64) -> Result<ExecutionPayloadHeader> {
+        execution_payload_header_from_state(&self.get_beacon_state(slot).await?, slot)
+    }
+}
+
+/// A single beacon state archived as an SSZ file named `<name>.<fork>.ssz`.
+/// Only the slot of that state can be served.
+pub struct StateFile<S> {
+    path: PathBuf,
+    preset: PhantomData<fn() -> S>,
+}
+
+impl<S> StateFile<S> {
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        ensure!(path.is_file(), "{} is not a file", path.display());
+        Ok(Self {
+            path,
+            preset: PhantomData,
+        })
+    }
+}
+
+impl<S: ProvableBeaconState> BeaconStateSource for StateFile<S> {
+    type BeaconState = S;
+
+    async fn get_beacon_state(&self, slot: u64) -> Result<S> {
+        let state: S = read_state_file(&self.path).await?;
+        ensure!(
+            state.slot() == slot,
+            "{} holds the beacon state at slot {}, not {slot}",
+            self.path.display(),
+            state.slot()
+        );
+        Ok(state)
+    }
+
+    async fn get_block_header(&self, slot: u64) -> Result<BeaconBlockHeader> {
+        block_header_from_state(&self.get_beacon_state(slot).await?, slot)
+    }
+
+    async fn get_execution_payload_header(&self, slot: u64) -> Result<ExecutionPayloadHeader> {
+        execution_payload_header_from_state(&self.get_beacon_state(slot).await?, slot)
+    }
+}
+
+/// The [BeaconStateSource] selected on the command line
+pub enum BeaconSource<S> {
+    Http(BeaconClient<S>),
+    Directory(StateDirectory<S>),
+    File(StateFile<S>),
+}
+
+impl<S: ProvableBeaconState> BeaconSource<S> {
+    /// Beacon API used for Steel beacon commitments. Only available when reading from beacon nodes
+    pub fn beacon_api_url(&self) -> Option<Url> {
+        match self {
+            BeaconSource::Http(client) => client.endpoints().first().cloned(),
+            BeaconSource::Directory(_) | BeaconSource::File(_) => None,
+        }
+    }
+
+    /// Latest finalized slot according to the beacon nodes. Archived states carry no information
+    /// about the current head of the chain, so finality is unknown for them.
+    pub async fn finalized_slot(&self) -> Result<Option<u64>> {
+        match self {
+            BeaconSource::Http(client) => Ok(Some(client.get_finalized_slot().await?)),
+            BeaconSource::Directory(_) | BeaconSource::File(_) => Ok(None),
+        }
+    }
+}
+
+impl<S: ProvableBeaconState> BeaconStateSource for BeaconSource<S> {
+    type BeaconState = S;
+
+    async fn resolve_block_slot(&self, slot: u64) -> Result<u64> {
+        match self {
+            BeaconSource::Http(source) => source.resolve_block_slot(slot).await,
+            BeaconSource::Directory(source) => source.resolve_block_slot(slot).await,
+            BeaconSource::File(source) => source.resolve_block_slot(slot).await,
+        }
+    }
+
+    async fn get_beacon_state(&self, slot: u64) -> Result<S> {
+        match self {
+            BeaconSource::Http(source) => BeaconStateSource::get_beacon_state(source, slot).await,
+            BeaconSource::Directory(source) => {
+                BeaconStateSource::get_beacon_state(source, slot).await
+            }
+            BeaconSource::File(source) => BeaconStateSource::get_beacon_state(source, slot).await,
+        }
+    }
+
+    async fn get_block_header(&self, slot: u64) -> Result<BeaconBlockHeader> {
+        match self {
+            BeaconSource::Http(source) => BeaconStateSource::get_block_header(source, slot).await,
+            BeaconSource::Directory(source) => {
+                BeaconStateSource::get_block_header(source, slot).await
+            }
+            BeaconSource::File(source) => BeaconStateSource::get_block_header(source, slot).await,
+        }
+    }
+
+    async fn get_execution_payload_header(&self, slot: u64) -> Result<ExecutionPayloadHeader> {
+        match self {
+            BeaconSource::Http(source) => {
+                BeaconStateSource::get_execution_payload_header(source, slot).await
+            }
+            BeaconSource::Directory(source) => {
+                BeaconStateSource::get_execution_payload_header(source, slot).await
+            }
+            BeaconSource::File(source) => {
+                BeaconStateSource::get_execution_payload_header(source, slot).await
+            }
+        }
+    }
+}
+
+/// Where beacon states are read from. Archived states take precedence over beacon nodes so that
+/// a `BEACON_RPC_URL` set in the environment does not need to be unset to work offline.
+#[derive(clap::Args, Debug)]
+pub struct BeaconSourceArgs {
+    /// Ethereum beacon node HTTP RPC endpoints, comma separated. Requests fail over to the
+    /// next endpoint and states are cross-checked against all of them.
+    #[clap(long, env, value_delimiter = ',')]
+    beacon_rpc_url: Vec<Url>,
+
+    /// Directory of beacon states archived as `<slot>.<fork>.ssz` files
+    #[clap(long, env)]
+    beacon_state_dir: Option<PathBuf>,
+
+    /// A single beacon state archived as a `<name>.<fork>.ssz` file
+    #[clap(long, env)]
+    beacon_state_file: Option<PathBuf>,
+
+    /// Number of times a failed beacon RPC request is retried before failing over to the next
+    /// endpoint
+    #[clap(long, env, default_value_t = 3)]
+    beacon_retries: u32,
+
+    /// Delay in milliseconds before the first retry of a beacon RPC request. Doubled for every
+    /// following retry
+    #[clap(long, env, default_value_t = 500)]
+    beacon_backoff: u64,
+
+    #[clap(flatten)]
+    cache: CacheArgs,
+}
+
+impl BeaconSourceArgs {
+    /// The selected source, which reads beacon states of the preset of `S`
+    pub fn source<S: ProvableBeaconState>(self) -> Result<BeaconSource<S>> {
+        if let Some(dir) = self.beacon_state_dir {
+            return Ok(BeaconSource::Directory(StateDirectory::new(dir)?));
+        }
+        if let Some(file) = self.beacon_state_file {
+            return Ok(BeaconSource::File(StateFile::new(file)?));
+        }
+        ensure!(
+            !self.beacon_rpc_url.is_empty(),
+            "one of --beacon-rpc-url, --beacon-state-dir or --beacon-state-file is required"
+        );
+        let retry = RetryConfig {
+            max_retries: self.beacon_retries,
+            initial_backoff: Duration::from_millis(self.beacon_backoff),
+            ..Default::default()
+        };
+        Ok(BeaconSource::Http(
+            BeaconClient::new_with_cache(self.beacon_rpc_url, self.cache.cache()?)?
+                .with_retry(retry),
+        ))
+    }
+}
+
+/// Reads an SSZ beacon state whose fork is given by the file name, e.g. `11649024.electra.ssz`
+async fn read_state_file<S: PresetBeaconState>(path: &Path) -> Result<S> {
+    let fork = fork_from_file_name(path)?;
+    let bytes = tokio::fs::read(path)
+        .await
+        .with_context(|| format!("reading beacon state {}", path.display()))?;
+    Ok(S::from_versioned_ssz(fork, &bytes)?)
+}
+
+fn fork_from_file_name(path: &Path) -> Result<Version> {
+    let fork = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_suffix(".ssz"))
+        .and_then(|stem| stem.rsplit_once('.'))
+        .map(|(_, fork)| fork)
+        .with_context(|| format!("expected a <name>.<fork>.ssz file: {}", path.display()))?;
+    Ok(fork.parse()?)
+}
+
+/// Header of the block at the slot of the state. The state root of the latest block is only filled
+/// in once the next slot is processed, so it is computed from the state.
+fn block_header_from_state<S: PresetBeaconState>(
+    state: &S,
+    slot: u64,
+) -> Result<BeaconBlockHeader> {
+    let mut header = state.latest_block_header().clone();
+    ensure!(
+        header.slot == slot,
+        "no beacon block at slot {slot}, the latest block in the state is at slot {}",
+        header.slot
+    );
+    if header.state_root == Root::default() {
+        header.state_root = state.hash_tree_root()?;
+    }
+    Ok(header)
+}
+
+/// Execution payload of the block at the slot of the state, taken from the latest execution
+/// payload header of the state
+fn execution_payload_header_from_state<S: PresetBeaconState>(
+    state: &S,
+    slot: u64,
+) -> Result<ExecutionPayloadHeader> {
+    ensure!(
+        state.latest_block_header().slot == slot,
+        "no beacon block at slot {slot}, the latest block in the state is at slot {}",
+        state.latest_block_header().slot
+    );
+    let payload = match (
+        state.latest_execution_block_hash(),
+        state.latest_execution_block_number(),
+        state.latest_execution_timestamp(),
+    ) {
+        (Some(block_hash), Some(block_number), Some(timestamp)) => Some(ExecutionPayloadHeader {
+            block_hash: B256::from_slice(block_hash.as_slice()),
+            block_number,
+            timestamp,
+        }),
+        _ => None,
+    };
+    match payload {
+        Some(payload) if !payload.block_hash.is_zero() => Ok(payload),
+        _ => bail!("beacon block at slot {slot} has no execution payload as it predates the merge"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beacon_state::mainnet::BeaconState;
+    use guest_io::network::MAINNET;
+    use test_utils::TestStateBuilder;
+
+    /// Writes an Electra state with a block at its slot to a fresh directory
+    fn archive_state(name: &str, execution_block_hash: Option<B256>) -> Result<(PathBuf, u64)> {
+        let slot = MAINNET.electra_fork_slot.unwrap();
+        let mut b = TestStateBuilder::new(slot);
+        b.with_lido_validators(2);
+        if let Some(block_hash) = execution_block_hash {
+            b.with_execution_block_hash(block_hash);
+        }
+        let BeaconState::Electra(mut state) = b.build() else {
+            unreachable!()
+        };
+        state.latest_block_header.slot = slot;
+
+        let dir = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join(format!("{slot}.electra.ssz")), serialize(&state)?)?;
+        Ok((dir, slot))
+    }
+
+    #[test]
+    fn fork_from_file_names() {
+        assert_eq!(
+            fork_from_file_name(Path::new("states/11649024.electra.ssz")).unwrap(),
+            Version::Electra
+        );
+        assert_eq!(
+            fork_from_file_name(Path::new("state.deneb.ssz")).unwrap(),
+            Version::Deneb
+        );
+        assert!(fork_from_file_name(Path::new("11649024.ssz")).is_err());
+        assert!(fork_from_file_name(Path::new("11649024.bogus.ssz")).is_err());
+    }
+
+    #[tokio::test]
+    async fn state_directory() -> Result<()> {
+        let block_hash = B256::repeat_byte(0xab);
+        let (dir, slot) = archive_state("state-directory", Some(block_hash))?;
+        let source = StateDirectory::<BeaconState>::new(&dir)?;
+
+        let state = source.get_beacon_state(slot).await?;
+        assert_eq!(state.slot(), slot);
+        assert_eq!(state.validators().len(), 2);
+
+        let header = source.get_block_header(slot).await?;
+        assert_eq!(header.slot, slot);
+        assert_eq!(header.state_root, state.hash_tree_root()?);
+
+        let payload = source.get_execution_payload_header(slot).await?;
+        assert_eq!(payload.block_hash, block_hash);
+
+        assert!(source.get_beacon_state(slot + 1).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn state_file() -> Result<()> {
+        let (dir, slot) = archive_state("state-file", None)?;
+        let source = StateFile::<BeaconState>::new(dir.join(format!("{slot}.electra.ssz")))?;
+
+        assert_eq!(source.get_beacon_state(slot).await?.slot(), slot);
+        // only the slot of the archived state can be served
+        assert!(source.get_beacon_state(slot - 1).await.is_err());
+        // a zero block hash means the block predates the merge
+        assert!(source.get_execution_payload_header(slot).await.is_err());
+        Ok(())
+    }
+}
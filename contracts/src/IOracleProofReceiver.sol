@@ -25,8 +25,30 @@ struct Report {
     uint256 totalExitedValidators;
 }
 
+/// @notice Proven figures reported alongside the LIP-23 report
+struct AuxiliaryReport {
+    uint256 pendingDepositsGwei;
+    uint256 pendingPartialWithdrawalsGwei;
+    uint256 pendingConsolidationsFromLido;
+    uint256 pendingConsolidationsToLido;
+    uint256 slashedValidators;
+    uint256 slashedBalanceGwei;
+}
+
 /// @title Receiver of oracle reports and proof data
 interface IOracleProofReceiver {
-    function update(uint256 refSlot, Report calldata r, bytes calldata seal, Steel.Commitment calldata commitment)
-        external;
+    /// @param refSlot Reference slot the report is stored under
+    /// @param blockSlot Slot of the last block at or before refSlot, whose state is reported on
+    /// @param childSlot Slot of the first block after refSlot, whose parent is the block at blockSlot
+    function update(
+        uint256 refSlot,
+        uint256 blockSlot,
+        uint256 childSlot,
+        Report calldata r,
+        AuxiliaryReport calldata aux,
+        bytes32 stateRoot,
+        uint8 fork,
+        bytes calldata seal,
+        Steel.Commitment calldata commitment
+    ) external;
 }
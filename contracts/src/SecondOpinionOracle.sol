@@ -20,7 +20,7 @@ import {IRiscZeroVerifier} from "risc0/IRiscZeroVerifier.sol";
 import {Steel, Beacon} from "risc0/steel/Steel.sol";
 import {ISecondOpinionOracle} from "./ISecondOpinionOracle.sol";
 import {ImageID} from "./ImageID.sol"; // auto-generated contract after running `cargo build`.
-import {Report, IOracleProofReceiver} from "./IOracleProofReceiver.sol";
+import {Report, AuxiliaryReport, IOracleProofReceiver} from "./IOracleProofReceiver.sol";
 
 /// @title LIP-23 Compatible Oracle implemented using RISC Zero
 contract SecondOpinionOracle is ISecondOpinionOracle, IOracleProofReceiver {
@@ -30,7 +30,11 @@ contract SecondOpinionOracle is ISecondOpinionOracle, IOracleProofReceiver {
         uint256 withdrawalVaultBalanceWei;
         uint256 totalDepositedValidators;
         uint256 totalExitedValidators;
+        AuxiliaryReport auxiliary;
         bytes32 blockRoot;
+        uint256 slot;
+        bytes32 stateRoot;
+        uint8 fork;
         Steel.Commitment commitment;
     }
 
@@ -50,7 +54,7 @@ contract SecondOpinionOracle is ISecondOpinionOracle, IOracleProofReceiver {
     mapping(uint256 => Report) public reports;
 
     /// @notice Emitted when a new report is stored.
-    event ReportUpdated(uint256 refSlot, Report r);
+    event ReportUpdated(uint256 refSlot, Report r, AuxiliaryReport aux, bytes32 stateRoot, uint8 fork);
 
     /// @notice Initialize the contract, binding it to a specified RISC Zero verifier.
     constructor(IRiscZeroVerifier _verifier, uint256 _genesis_block_timestamp) {
@@ -58,20 +62,37 @@ contract SecondOpinionOracle is ISecondOpinionOracle, IOracleProofReceiver {
         genesis_block_timestamp = _genesis_block_timestamp;
     }
 
-    /// @notice Set an oracle report for a given slot by verifying the ZK proof
-    function update(uint256 refSlot, Report calldata r, bytes calldata seal, Steel.Commitment calldata commitment)
-        external
-    {
+    /// @notice Set an oracle report for a given slot by verifying the ZK proof.
+    /// @dev Following LIP-23 the report for a missed refSlot is based on the last block before it.
+    /// The root of that block is read from the beacon roots of its child, which is the first block
+    /// after refSlot. Neither refSlot nor refSlot + 1 has to contain a block.
+    function update(
+        uint256 refSlot,
+        uint256 blockSlot,
+        uint256 childSlot,
+        Report calldata r,
+        AuxiliaryReport calldata aux,
+        bytes32 stateRoot,
+        uint8 fork,
+        bytes calldata seal,
+        Steel.Commitment calldata commitment
+    ) external {
         require(Steel.validateCommitment(commitment), "Invalid commitment");
 
-        bytes32 blockRoot = Beacon.parentBlockRoot(_timestampAtSlot(refSlot + 1));
+        // no block between the proven block and its child means it is the last block at or before refSlot
+        require(blockSlot <= refSlot && refSlot < childSlot, "Block is not the last block at refSlot");
+        bytes32 blockRoot = Beacon.parentBlockRoot(_timestampAtSlot(childSlot));
 
         Journal memory journal = Journal({
             clBalanceGwei: r.clBalanceGwei,
             withdrawalVaultBalanceWei: r.withdrawalVaultBalanceWei,
             totalDepositedValidators: r.totalDepositedValidators,
             totalExitedValidators: r.totalExitedValidators,
+            auxiliary: aux,
             blockRoot: blockRoot,
+            slot: blockSlot,
+            stateRoot: stateRoot,
+            fork: fork,
             commitment: commitment
         });
 
@@ -79,7 +100,7 @@ contract SecondOpinionOracle is ISecondOpinionOracle, IOracleProofReceiver {
 
         // report is now considered valid for the given slot and can be stored
         reports[refSlot] = r;
-        emit ReportUpdated(refSlot, r);
+        emit ReportUpdated(refSlot, r, aux, stateRoot, fork);
     }
 
     /// @notice Returns the number stored.
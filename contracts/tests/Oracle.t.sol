@@ -17,10 +17,211 @@
 pragma solidity ^0.8.20;
 
 import {RiscZeroCheats} from "risc0/test/RiscZeroCheats.sol";
+import {RiscZeroMockVerifier} from "risc0/test/RiscZeroMockVerifier.sol";
+import {VerificationFailed} from "risc0/IRiscZeroVerifier.sol";
+import {Steel, Encoding} from "risc0/steel/Steel.sol";
 import {Test} from "forge-std/Test.sol";
+import {SecondOpinionOracle} from "../src/SecondOpinionOracle.sol";
+import {Report, AuxiliaryReport} from "../src/IOracleProofReceiver.sol";
 
 contract OracleTest is RiscZeroCheats, Test {
-    function testNoop() public {
-        assertEq(true, true);
+    /// @notice EIP-4788 beacon roots contract
+    address constant BEACON_ROOTS_ADDRESS = 0x000F3df6D732807Ef1319fB7B8bB8522d0Beac02;
+    uint256 constant GENESIS_BLOCK_TIMESTAMP = 1606824023;
+    uint256 constant REF_SLOT = 11649024;
+    bytes32 constant BLOCK_ROOT = keccak256("block root");
+    bytes32 constant STATE_ROOT = keccak256("state root");
+    uint8 constant ELECTRA = 5;
+
+    event ReportUpdated(uint256 refSlot, Report r, AuxiliaryReport aux, bytes32 stateRoot, uint8 fork);
+
+    RiscZeroMockVerifier verifier;
+    SecondOpinionOracle oracle;
+
+    function setUp() public {
+        verifier = new RiscZeroMockVerifier(bytes4(0));
+        oracle = new SecondOpinionOracle(verifier, GENESIS_BLOCK_TIMESTAMP);
+
+        // like the beacon roots contract, revert for timestamps without a block
+        vm.etch(BEACON_ROOTS_ADDRESS, hex"5f5ffd");
+        vm.warp(_timestampAtSlot(REF_SLOT + 32));
+        vm.roll(22000000);
+        vm.setBlockhash(block.number - 1, keccak256("execution block"));
+    }
+
+    function testUpdate() public {
+        _mockParentBlockRoot(REF_SLOT + 1, BLOCK_ROOT);
+        bytes memory seal = _seal(REF_SLOT, BLOCK_ROOT, _report(), _aux(), STATE_ROOT, ELECTRA);
+
+        vm.expectEmit(address(oracle));
+        emit ReportUpdated(REF_SLOT, _report(), _aux(), STATE_ROOT, ELECTRA);
+        oracle.update(REF_SLOT, REF_SLOT, REF_SLOT + 1, _report(), _aux(), STATE_ROOT, ELECTRA, seal, _commitment());
+
+        (
+            bool success,
+            uint256 clBalanceGwei,
+            uint256 withdrawalVaultBalanceWei,
+            uint256 totalDepositedValidators,
+            uint256 totalExitedValidators
+        ) = oracle.getReport(REF_SLOT);
+        assertTrue(success);
+        assertEq(clBalanceGwei, _report().clBalanceGwei);
+        assertEq(withdrawalVaultBalanceWei, _report().withdrawalVaultBalanceWei);
+        assertEq(totalDepositedValidators, _report().totalDepositedValidators);
+        assertEq(totalExitedValidators, _report().totalExitedValidators);
+    }
+
+    function testUpdateMissedRefSlot() public {
+        // neither refSlot nor the slot after it contain a block
+        uint256 blockSlot = REF_SLOT - 2;
+        uint256 childSlot = REF_SLOT + 2;
+        _mockParentBlockRoot(childSlot, BLOCK_ROOT);
+        bytes memory seal = _seal(blockSlot, BLOCK_ROOT, _report(), _aux(), STATE_ROOT, ELECTRA);
+
+        oracle.update(REF_SLOT, blockSlot, childSlot, _report(), _aux(), STATE_ROOT, ELECTRA, seal, _commitment());
+
+        // the report is stored under the reference slot, not the slot of the proven block
+        (bool success,,,,) = oracle.getReport(REF_SLOT);
+        assertTrue(success);
+        (success,,,,) = oracle.getReport(blockSlot);
+        assertFalse(success);
+    }
+
+    function testRejectsChildSlotNotAfterRefSlot() public {
+        _mockParentBlockRoot(REF_SLOT, BLOCK_ROOT);
+        bytes memory seal = _seal(REF_SLOT - 1, BLOCK_ROOT, _report(), _aux(), STATE_ROOT, ELECTRA);
+
+        vm.expectRevert("Block is not the last block at refSlot");
+        oracle.update(REF_SLOT, REF_SLOT - 1, REF_SLOT, _report(), _aux(), STATE_ROOT, ELECTRA, seal, _commitment());
+    }
+
+    function testRejectsBlockSlotAfterRefSlot() public {
+        _mockParentBlockRoot(REF_SLOT + 2, BLOCK_ROOT);
+        bytes memory seal = _seal(REF_SLOT + 1, BLOCK_ROOT, _report(), _aux(), STATE_ROOT, ELECTRA);
+
+        vm.expectRevert("Block is not the last block at refSlot");
+        oracle.update(
+            REF_SLOT, REF_SLOT + 1, REF_SLOT + 2, _report(), _aux(), STATE_ROOT, ELECTRA, seal, _commitment()
+        );
+    }
+
+    function testRejectsMismatchedParentRoot() public {
+        // the child block's parent is not the block the proof is about
+        _mockParentBlockRoot(REF_SLOT + 1, keccak256("other block root"));
+        bytes memory seal = _seal(REF_SLOT, BLOCK_ROOT, _report(), _aux(), STATE_ROOT, ELECTRA);
+
+        vm.expectRevert(VerificationFailed.selector);
+        oracle.update(REF_SLOT, REF_SLOT, REF_SLOT + 1, _report(), _aux(), STATE_ROOT, ELECTRA, seal, _commitment());
+    }
+
+    function testRejectsChildSlotWithoutBlock() public {
+        bytes memory seal = _seal(REF_SLOT, BLOCK_ROOT, _report(), _aux(), STATE_ROOT, ELECTRA);
+
+        vm.expectRevert();
+        oracle.update(REF_SLOT, REF_SLOT, REF_SLOT + 1, _report(), _aux(), STATE_ROOT, ELECTRA, seal, _commitment());
+    }
+
+    function testJournalCommitsStateRootAndFork() public {
+        _mockParentBlockRoot(REF_SLOT + 1, BLOCK_ROOT);
+        bytes memory seal = _seal(REF_SLOT, BLOCK_ROOT, _report(), _aux(), STATE_ROOT, ELECTRA);
+
+        bytes32 otherStateRoot = keccak256("other state root");
+        vm.expectRevert(VerificationFailed.selector);
+        oracle.update(
+            REF_SLOT, REF_SLOT, REF_SLOT + 1, _report(), _aux(), otherStateRoot, ELECTRA, seal, _commitment()
+        );
+
+        vm.expectRevert(VerificationFailed.selector);
+        oracle.update(
+            REF_SLOT, REF_SLOT, REF_SLOT + 1, _report(), _aux(), STATE_ROOT, ELECTRA + 1, seal, _commitment()
+        );
+    }
+
+    function testJournalCommitsAuxiliaryReport() public {
+        _mockParentBlockRoot(REF_SLOT + 1, BLOCK_ROOT);
+        bytes memory seal = _seal(REF_SLOT, BLOCK_ROOT, _report(), _aux(), STATE_ROOT, ELECTRA);
+
+        AuxiliaryReport memory aux = _aux();
+        aux.slashedBalanceGwei += 1;
+        vm.expectRevert(VerificationFailed.selector);
+        oracle.update(REF_SLOT, REF_SLOT, REF_SLOT + 1, _report(), aux, STATE_ROOT, ELECTRA, seal, _commitment());
+
+        aux = _aux();
+        aux.pendingDepositsGwei += 1;
+        vm.expectRevert(VerificationFailed.selector);
+        oracle.update(REF_SLOT, REF_SLOT, REF_SLOT + 1, _report(), aux, STATE_ROOT, ELECTRA, seal, _commitment());
+    }
+
+    function testJournalCommitsBlockSlot() public {
+        // the proof is about the block at REF_SLOT - 1 but claims to be about an earlier one
+        _mockParentBlockRoot(REF_SLOT + 1, BLOCK_ROOT);
+        bytes memory seal = _seal(REF_SLOT - 1, BLOCK_ROOT, _report(), _aux(), STATE_ROOT, ELECTRA);
+
+        vm.expectRevert(VerificationFailed.selector);
+        oracle.update(
+            REF_SLOT, REF_SLOT - 2, REF_SLOT + 1, _report(), _aux(), STATE_ROOT, ELECTRA, seal, _commitment()
+        );
+    }
+
+    function _report() internal pure returns (Report memory) {
+        return Report({
+            clBalanceGwei: 9_000_000_000_000_000,
+            withdrawalVaultBalanceWei: 4_000 ether,
+            totalDepositedValidators: 420_000,
+            totalExitedValidators: 12_000
+        });
+    }
+
+    function _aux() internal pure returns (AuxiliaryReport memory) {
+        return AuxiliaryReport({
+            pendingDepositsGwei: 64_000_000_000,
+            pendingPartialWithdrawalsGwei: 5_000_000_000,
+            pendingConsolidationsFromLido: 1,
+            pendingConsolidationsToLido: 2,
+            slashedValidators: 3,
+            slashedBalanceGwei: 90_000_000_000
+        });
+    }
+
+    /// @notice Steel commitment to the previous execution block
+    function _commitment() internal view returns (Steel.Commitment memory) {
+        uint256 blockNumber = block.number - 1;
+        return Steel.Commitment({
+            id: Encoding.encodeVersionedID(uint240(blockNumber), 0),
+            digest: blockhash(blockNumber),
+            configID: bytes32(0)
+        });
+    }
+
+    /// @notice Seal of a mock receipt for the journal the guest writes
+    function _seal(
+        uint256 blockSlot,
+        bytes32 blockRoot,
+        Report memory r,
+        AuxiliaryReport memory aux,
+        bytes32 stateRoot,
+        uint8 fork
+    ) internal returns (bytes memory) {
+        SecondOpinionOracle.Journal memory journal = SecondOpinionOracle.Journal({
+            clBalanceGwei: r.clBalanceGwei,
+            withdrawalVaultBalanceWei: r.withdrawalVaultBalanceWei,
+            totalDepositedValidators: r.totalDepositedValidators,
+            totalExitedValidators: r.totalExitedValidators,
+            auxiliary: aux,
+            blockRoot: blockRoot,
+            slot: blockSlot,
+            stateRoot: stateRoot,
+            fork: fork,
+            commitment: _commitment()
+        });
+        return verifier.mockProve(oracle.imageId(), sha256(abi.encode(journal))).seal;
+    }
+
+    function _mockParentBlockRoot(uint256 slot, bytes32 root) internal {
+        vm.mockCall(BEACON_ROOTS_ADDRESS, abi.encode(_timestampAtSlot(slot)), abi.encode(root));
+    }
+
+    function _timestampAtSlot(uint256 slot) internal pure returns (uint256) {
+        return GENESIS_BLOCK_TIMESTAMP + slot * 12;
     }
 }
@@ -6,23 +6,90 @@ use ethereum_consensus::{
         self, Checkpoint, Fork, ParticipationFlags, PendingAttestation, JUSTIFICATION_BITS_LENGTH,
     },
     phase0::{self, BeaconBlockHeader, Validator},
-    primitives::{Bytes32, Gwei, Root, Slot},
+    primitives::{Bytes32, Gwei, Hash32, Root, Slot},
     ssz::prelude::*,
-    Fork as Version,
 };
+use std::fmt::Display;
+
+/// Consensus forks, each of which has its own BeaconState container.
+/// Serialized as the lowercase fork name used by the beacon API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Version {
+    Phase0,
+    Altair,
+    Bellatrix,
+    Capella,
+    Deneb,
+    Electra,
+    Fulu,
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Version::Phase0 => "phase0",
+            Version::Altair => "altair",
+            Version::Bellatrix => "bellatrix",
+            Version::Capella => "capella",
+            Version::Deneb => "deneb",
+            Version::Electra => "electra",
+            Version::Fulu => "fulu",
+        };
+        f.write_str(name)
+    }
+}
+
+impl std::str::FromStr for Version {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "phase0" => Ok(Version::Phase0),
+            "altair" => Ok(Version::Altair),
+            "bellatrix" => Ok(Version::Bellatrix),
+            "capella" => Ok(Version::Capella),
+            "deneb" => Ok(Version::Deneb),
+            "electra" => Ok(Version::Electra),
+            "fulu" => Ok(Version::Fulu),
+            _ => Err(Error::UnknownVersion(s.to_string())),
+        }
+    }
+}
 
 pub mod mainnet {
     use ethereum_consensus::altair::mainnet::SYNC_COMMITTEE_SIZE;
-    use ethereum_consensus::bellatrix::mainnet::{BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES};
+    use ethereum_consensus::bellatrix::mainnet::{
+        BYTES_PER_LOGS_BLOOM, MAX_BYTES_PER_TRANSACTION, MAX_EXTRA_DATA_BYTES,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+    };
+    use ethereum_consensus::capella::mainnet::{
+        MAX_BLS_TO_EXECUTION_CHANGES, MAX_WITHDRAWALS_PER_PAYLOAD,
+    };
+    use ethereum_consensus::deneb::mainnet::MAX_BLOB_COMMITMENTS_PER_BLOCK;
     use ethereum_consensus::electra::mainnet::{
         PENDING_BALANCE_DEPOSITS_LIMIT, PENDING_CONSOLIDATIONS_LIMIT,
         PENDING_PARTIAL_WITHDRAWALS_LIMIT,
     };
     use ethereum_consensus::phase0::mainnet::{
         EPOCHS_PER_HISTORICAL_VECTOR, EPOCHS_PER_SLASHINGS_VECTOR, ETH1_DATA_VOTES_BOUND,
-        HISTORICAL_ROOTS_LIMIT, MAX_VALIDATORS_PER_COMMITTEE, PENDING_ATTESTATIONS_BOUND,
-        SLOTS_PER_HISTORICAL_ROOT, VALIDATOR_REGISTRY_LIMIT,
+        HISTORICAL_ROOTS_LIMIT, MAX_DEPOSITS, MAX_PROPOSER_SLASHINGS, MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_VOLUNTARY_EXITS, PENDING_ATTESTATIONS_BOUND, SLOTS_PER_HISTORICAL_ROOT,
+        VALIDATOR_REGISTRY_LIMIT,
     };
+
+    pub const MAX_COMMITTEES_PER_SLOT: usize = 64;
+    /// MAX_VALIDATORS_PER_COMMITTEE * MAX_COMMITTEES_PER_SLOT
+    pub const MAX_VALIDATORS_PER_SLOT: usize = 131072;
+    pub const MAX_ATTESTER_SLASHINGS_ELECTRA: usize = 1;
+    pub const MAX_ATTESTATIONS_ELECTRA: usize = 8;
+    pub const MAX_DEPOSIT_REQUESTS_PER_PAYLOAD: usize = 8192;
+    pub const MAX_WITHDRAWAL_REQUESTS_PER_PAYLOAD: usize = 16;
+    pub const MAX_CONSOLIDATION_REQUESTS_PER_PAYLOAD: usize = 2;
+
+    /// (MIN_SEED_LOOKAHEAD + 1) * SLOTS_PER_EPOCH
+    pub const PROPOSER_LOOKAHEAD_SIZE: usize = 64;
+
     pub type BeaconState = super::BeaconState<
         SLOTS_PER_HISTORICAL_ROOT,
         HISTORICAL_ROOTS_LIMIT,
@@ -38,6 +105,7 @@ pub mod mainnet {
         PENDING_BALANCE_DEPOSITS_LIMIT,
         PENDING_PARTIAL_WITHDRAWALS_LIMIT,
         PENDING_CONSOLIDATIONS_LIMIT,
+        PROPOSER_LOOKAHEAD_SIZE,
     >;
     pub type ElectraBeaconState = super::electra::BeaconState<
         SLOTS_PER_HISTORICAL_ROOT,
@@ -54,20 +122,164 @@ pub mod mainnet {
         PENDING_PARTIAL_WITHDRAWALS_LIMIT,
         PENDING_CONSOLIDATIONS_LIMIT,
     >;
+    pub type FuluBeaconState = super::fulu::BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        PENDING_BALANCE_DEPOSITS_LIMIT,
+        PENDING_PARTIAL_WITHDRAWALS_LIMIT,
+        PENDING_CONSOLIDATIONS_LIMIT,
+        PROPOSER_LOOKAHEAD_SIZE,
+    >;
+
+    pub type ElectraBeaconBlock = super::electra::BeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_SLOT,
+        MAX_COMMITTEES_PER_SLOT,
+        MAX_ATTESTER_SLASHINGS_ELECTRA,
+        MAX_ATTESTATIONS_ELECTRA,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        MAX_DEPOSIT_REQUESTS_PER_PAYLOAD,
+        MAX_WITHDRAWAL_REQUESTS_PER_PAYLOAD,
+        MAX_CONSOLIDATION_REQUESTS_PER_PAYLOAD,
+    >;
 }
 
-mod electra {
+pub mod minimal {
+    use ethereum_consensus::altair::minimal::SYNC_COMMITTEE_SIZE;
+    use ethereum_consensus::bellatrix::minimal::{
+        BYTES_PER_LOGS_BLOOM, MAX_BYTES_PER_TRANSACTION, MAX_EXTRA_DATA_BYTES,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+    };
+    use ethereum_consensus::capella::minimal::{
+        MAX_BLS_TO_EXECUTION_CHANGES, MAX_WITHDRAWALS_PER_PAYLOAD,
+    };
+    use ethereum_consensus::deneb::minimal::MAX_BLOB_COMMITMENTS_PER_BLOCK;
+    use ethereum_consensus::electra::minimal::{
+        PENDING_BALANCE_DEPOSITS_LIMIT, PENDING_CONSOLIDATIONS_LIMIT,
+        PENDING_PARTIAL_WITHDRAWALS_LIMIT,
+    };
+    use ethereum_consensus::phase0::minimal::{
+        EPOCHS_PER_HISTORICAL_VECTOR, EPOCHS_PER_SLASHINGS_VECTOR, ETH1_DATA_VOTES_BOUND,
+        HISTORICAL_ROOTS_LIMIT, MAX_DEPOSITS, MAX_PROPOSER_SLASHINGS, MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_VOLUNTARY_EXITS, PENDING_ATTESTATIONS_BOUND, SLOTS_PER_HISTORICAL_ROOT,
+        VALIDATOR_REGISTRY_LIMIT,
+    };
+
+    pub const MAX_COMMITTEES_PER_SLOT: usize = 4;
+    /// MAX_VALIDATORS_PER_COMMITTEE * MAX_COMMITTEES_PER_SLOT
+    pub const MAX_VALIDATORS_PER_SLOT: usize = 8192;
+    pub const MAX_ATTESTER_SLASHINGS_ELECTRA: usize = 1;
+    pub const MAX_ATTESTATIONS_ELECTRA: usize = 8;
+    pub const MAX_DEPOSIT_REQUESTS_PER_PAYLOAD: usize = 4;
+    pub const MAX_WITHDRAWAL_REQUESTS_PER_PAYLOAD: usize = 2;
+    pub const MAX_CONSOLIDATION_REQUESTS_PER_PAYLOAD: usize = 2;
+
+    /// (MIN_SEED_LOOKAHEAD + 1) * SLOTS_PER_EPOCH
+    pub const PROPOSER_LOOKAHEAD_SIZE: usize = 16;
+
+    pub type BeaconState = super::BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        PENDING_BALANCE_DEPOSITS_LIMIT,
+        PENDING_PARTIAL_WITHDRAWALS_LIMIT,
+        PENDING_CONSOLIDATIONS_LIMIT,
+        PROPOSER_LOOKAHEAD_SIZE,
+    >;
+    pub type ElectraBeaconState = super::electra::BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        PENDING_BALANCE_DEPOSITS_LIMIT,
+        PENDING_PARTIAL_WITHDRAWALS_LIMIT,
+        PENDING_CONSOLIDATIONS_LIMIT,
+    >;
+    pub type FuluBeaconState = super::fulu::BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        PENDING_BALANCE_DEPOSITS_LIMIT,
+        PENDING_PARTIAL_WITHDRAWALS_LIMIT,
+        PENDING_CONSOLIDATIONS_LIMIT,
+        PROPOSER_LOOKAHEAD_SIZE,
+    >;
+
+    pub type ElectraBeaconBlock = super::electra::BeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_SLOT,
+        MAX_COMMITTEES_PER_SLOT,
+        MAX_ATTESTER_SLASHINGS_ELECTRA,
+        MAX_ATTESTATIONS_ELECTRA,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        MAX_DEPOSIT_REQUESTS_PER_PAYLOAD,
+        MAX_WITHDRAWAL_REQUESTS_PER_PAYLOAD,
+        MAX_CONSOLIDATION_REQUESTS_PER_PAYLOAD,
+    >;
+}
+
+pub mod electra {
     use ethereum_consensus::crypto::{PublicKey as BlsPublicKey, Signature as BlsSignature};
-    use ethereum_consensus::electra::PendingConsolidation;
+    pub use ethereum_consensus::electra::PendingConsolidation;
     use ethereum_consensus::serde::{as_str, seq_of_str};
     use ethereum_consensus::{
-        altair::SyncCommittee,
-        capella::HistoricalSummary,
+        altair::{SyncAggregate, SyncCommittee},
+        capella::{HistoricalSummary, SignedBlsToExecutionChange},
+        deneb::{polynomial_commitments::KzgCommitment, ExecutionPayload},
         phase0::{
-            BeaconBlockHeader, Checkpoint, Eth1Data, Fork, Validator, JUSTIFICATION_BITS_LENGTH,
+            AttestationData, BeaconBlockHeader, Checkpoint, Deposit, Eth1Data, Fork,
+            ProposerSlashing, SignedVoluntaryExit, Validator, JUSTIFICATION_BITS_LENGTH,
         },
         primitives::{
-            Bytes32, Epoch, Gwei, ParticipationFlags, Root, Slot, ValidatorIndex, WithdrawalIndex,
+            Bytes32, Epoch, ExecutionAddress, Gwei, ParticipationFlags, Root, Slot, ValidatorIndex,
+            WithdrawalIndex,
         },
     };
     use ssz_rs::prelude::*;
@@ -83,7 +295,7 @@ mod electra {
         pub amount: Gwei,
         pub signature: BlsSignature,
         #[serde(with = "as_str")]
-        slot: Slot,
+        pub slot: Slot,
     }
 
     #[derive(
@@ -176,6 +388,312 @@ mod electra {
             List<PendingPartialWithdrawal, PENDING_PARTIAL_WITHDRAWALS_LIMIT>,
         pub pending_consolidations: List<PendingConsolidation, PENDING_CONSOLIDATIONS_LIMIT>,
     }
+
+    #[derive(
+        Default, Debug, Clone, SimpleSerialize, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+    )]
+    pub struct DepositRequest {
+        #[serde(rename = "pubkey")]
+        pub public_key: BlsPublicKey,
+        pub withdrawal_credentials: Bytes32,
+        #[serde(with = "as_str")]
+        pub amount: Gwei,
+        pub signature: BlsSignature,
+        #[serde(with = "as_str")]
+        pub index: u64,
+    }
+
+    #[derive(
+        Default, Debug, Clone, SimpleSerialize, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+    )]
+    pub struct WithdrawalRequest {
+        pub source_address: ExecutionAddress,
+        #[serde(rename = "validator_pubkey")]
+        pub validator_public_key: BlsPublicKey,
+        #[serde(with = "as_str")]
+        pub amount: Gwei,
+    }
+
+    #[derive(
+        Default, Debug, Clone, SimpleSerialize, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+    )]
+    pub struct ConsolidationRequest {
+        pub source_address: ExecutionAddress,
+        #[serde(rename = "source_pubkey")]
+        pub source_public_key: BlsPublicKey,
+        #[serde(rename = "target_pubkey")]
+        pub target_public_key: BlsPublicKey,
+    }
+
+    /// Requests from the execution layer, added to the block body in Electra (EIP-7685)
+    #[derive(
+        Default, Debug, Clone, SimpleSerialize, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+    )]
+    pub struct ExecutionRequests<
+        const MAX_DEPOSIT_REQUESTS_PER_PAYLOAD: usize,
+        const MAX_WITHDRAWAL_REQUESTS_PER_PAYLOAD: usize,
+        const MAX_CONSOLIDATION_REQUESTS_PER_PAYLOAD: usize,
+    > {
+        pub deposits: List<DepositRequest, MAX_DEPOSIT_REQUESTS_PER_PAYLOAD>,
+        pub withdrawals: List<WithdrawalRequest, MAX_WITHDRAWAL_REQUESTS_PER_PAYLOAD>,
+        pub consolidations: List<ConsolidationRequest, MAX_CONSOLIDATION_REQUESTS_PER_PAYLOAD>,
+    }
+
+    /// Attestations aggregated across the committees of a slot (EIP-7549)
+    #[derive(
+        Default, Debug, Clone, SimpleSerialize, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+    )]
+    pub struct Attestation<
+        const MAX_VALIDATORS_PER_SLOT: usize,
+        const MAX_COMMITTEES_PER_SLOT: usize,
+    > {
+        pub aggregation_bits: Bitlist<MAX_VALIDATORS_PER_SLOT>,
+        pub data: AttestationData,
+        pub signature: BlsSignature,
+        pub committee_bits: Bitvector<MAX_COMMITTEES_PER_SLOT>,
+    }
+
+    #[derive(
+        Default, Debug, Clone, SimpleSerialize, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+    )]
+    pub struct IndexedAttestation<const MAX_VALIDATORS_PER_SLOT: usize> {
+        #[serde(with = "seq_of_str")]
+        pub attesting_indices: List<ValidatorIndex, MAX_VALIDATORS_PER_SLOT>,
+        pub data: AttestationData,
+        pub signature: BlsSignature,
+    }
+
+    #[derive(
+        Default, Debug, Clone, SimpleSerialize, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+    )]
+    pub struct AttesterSlashing<const MAX_VALIDATORS_PER_SLOT: usize> {
+        pub attestation_1: IndexedAttestation<MAX_VALIDATORS_PER_SLOT>,
+        pub attestation_2: IndexedAttestation<MAX_VALIDATORS_PER_SLOT>,
+    }
+
+    /// Fulu uses the same block body
+    #[derive(
+        Default, Debug, Clone, SimpleSerialize, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+    )]
+    pub struct BeaconBlockBody<
+        const MAX_PROPOSER_SLASHINGS: usize,
+        const MAX_VALIDATORS_PER_SLOT: usize,
+        const MAX_COMMITTEES_PER_SLOT: usize,
+        const MAX_ATTESTER_SLASHINGS: usize,
+        const MAX_ATTESTATIONS: usize,
+        const MAX_DEPOSITS: usize,
+        const MAX_VOLUNTARY_EXITS: usize,
+        const SYNC_COMMITTEE_SIZE: usize,
+        const BYTES_PER_LOGS_BLOOM: usize,
+        const MAX_EXTRA_DATA_BYTES: usize,
+        const MAX_BYTES_PER_TRANSACTION: usize,
+        const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+        const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+        const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+        const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+        const MAX_DEPOSIT_REQUESTS_PER_PAYLOAD: usize,
+        const MAX_WITHDRAWAL_REQUESTS_PER_PAYLOAD: usize,
+        const MAX_CONSOLIDATION_REQUESTS_PER_PAYLOAD: usize,
+    > {
+        pub randao_reveal: BlsSignature,
+        pub eth1_data: Eth1Data,
+        pub graffiti: Bytes32,
+        pub proposer_slashings: List<ProposerSlashing, MAX_PROPOSER_SLASHINGS>,
+        pub attester_slashings:
+            List<AttesterSlashing<MAX_VALIDATORS_PER_SLOT>, MAX_ATTESTER_SLASHINGS>,
+        pub attestations:
+            List<Attestation<MAX_VALIDATORS_PER_SLOT, MAX_COMMITTEES_PER_SLOT>, MAX_ATTESTATIONS>,
+        pub deposits: List<Deposit, MAX_DEPOSITS>,
+        pub voluntary_exits: List<SignedVoluntaryExit, MAX_VOLUNTARY_EXITS>,
+        pub sync_aggregate: SyncAggregate<SYNC_COMMITTEE_SIZE>,
+        pub execution_payload: ExecutionPayload<
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+        >,
+        pub bls_to_execution_changes:
+            List<SignedBlsToExecutionChange, MAX_BLS_TO_EXECUTION_CHANGES>,
+        pub blob_kzg_commitments: List<KzgCommitment, MAX_BLOB_COMMITMENTS_PER_BLOCK>,
+        pub execution_requests: ExecutionRequests<
+            MAX_DEPOSIT_REQUESTS_PER_PAYLOAD,
+            MAX_WITHDRAWAL_REQUESTS_PER_PAYLOAD,
+            MAX_CONSOLIDATION_REQUESTS_PER_PAYLOAD,
+        >,
+    }
+
+    #[derive(
+        Default, Debug, Clone, SimpleSerialize, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+    )]
+    pub struct BeaconBlock<
+        const MAX_PROPOSER_SLASHINGS: usize,
+        const MAX_VALIDATORS_PER_SLOT: usize,
+        const MAX_COMMITTEES_PER_SLOT: usize,
+        const MAX_ATTESTER_SLASHINGS: usize,
+        const MAX_ATTESTATIONS: usize,
+        const MAX_DEPOSITS: usize,
+        const MAX_VOLUNTARY_EXITS: usize,
+        const SYNC_COMMITTEE_SIZE: usize,
+        const BYTES_PER_LOGS_BLOOM: usize,
+        const MAX_EXTRA_DATA_BYTES: usize,
+        const MAX_BYTES_PER_TRANSACTION: usize,
+        const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+        const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+        const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+        const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+        const MAX_DEPOSIT_REQUESTS_PER_PAYLOAD: usize,
+        const MAX_WITHDRAWAL_REQUESTS_PER_PAYLOAD: usize,
+        const MAX_CONSOLIDATION_REQUESTS_PER_PAYLOAD: usize,
+    > {
+        #[serde(with = "as_str")]
+        pub slot: Slot,
+        #[serde(with = "as_str")]
+        pub proposer_index: ValidatorIndex,
+        pub parent_root: Root,
+        pub state_root: Root,
+        pub body: BeaconBlockBody<
+            MAX_PROPOSER_SLASHINGS,
+            MAX_VALIDATORS_PER_SLOT,
+            MAX_COMMITTEES_PER_SLOT,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+            MAX_BLS_TO_EXECUTION_CHANGES,
+            MAX_BLOB_COMMITMENTS_PER_BLOCK,
+            MAX_DEPOSIT_REQUESTS_PER_PAYLOAD,
+            MAX_WITHDRAWAL_REQUESTS_PER_PAYLOAD,
+            MAX_CONSOLIDATION_REQUESTS_PER_PAYLOAD,
+        >,
+    }
+}
+
+/// Fulu adds the proposer lookahead to the end of the Electra BeaconState
+pub mod fulu {
+    pub use super::electra::{PendingConsolidation, PendingDeposit, PendingPartialWithdrawal};
+    use ethereum_consensus::serde::{as_str, seq_of_str};
+    use ethereum_consensus::{
+        altair::SyncCommittee,
+        capella::HistoricalSummary,
+        phase0::{
+            BeaconBlockHeader, Checkpoint, Eth1Data, Fork, Validator, JUSTIFICATION_BITS_LENGTH,
+        },
+        primitives::{
+            Bytes32, Epoch, Gwei, ParticipationFlags, Root, Slot, ValidatorIndex, WithdrawalIndex,
+        },
+    };
+    use ssz_rs::prelude::*;
+
+    #[derive(
+        Default, Debug, SimpleSerialize, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+    )]
+    pub struct BeaconState<
+        const SLOTS_PER_HISTORICAL_ROOT: usize,
+        const HISTORICAL_ROOTS_LIMIT: usize,
+        const ETH1_DATA_VOTES_BOUND: usize,
+        const VALIDATOR_REGISTRY_LIMIT: usize,
+        const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+        const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+        const MAX_VALIDATORS_PER_COMMITTEE: usize,
+        const SYNC_COMMITTEE_SIZE: usize,
+        const BYTES_PER_LOGS_BLOOM: usize,
+        const MAX_EXTRA_DATA_BYTES: usize,
+        const PENDING_DEPOSITS_LIMIT: usize,
+        const PENDING_PARTIAL_WITHDRAWALS_LIMIT: usize,
+        const PENDING_CONSOLIDATIONS_LIMIT: usize,
+        const PROPOSER_LOOKAHEAD_SIZE: usize,
+    > {
+        #[serde(with = "as_str")]
+        pub genesis_time: u64,
+        pub genesis_validators_root: Root,
+        #[serde(with = "as_str")]
+        pub slot: Slot,
+        pub fork: Fork,
+        pub latest_block_header: BeaconBlockHeader,
+        pub block_roots: Vector<Root, SLOTS_PER_HISTORICAL_ROOT>,
+        pub state_roots: Vector<Root, SLOTS_PER_HISTORICAL_ROOT>,
+        pub historical_roots: List<Root, HISTORICAL_ROOTS_LIMIT>,
+        pub eth1_data: Eth1Data,
+        pub eth1_data_votes: List<Eth1Data, ETH1_DATA_VOTES_BOUND>,
+        #[serde(with = "as_str")]
+        pub eth1_deposit_index: u64,
+        pub validators: List<Validator, VALIDATOR_REGISTRY_LIMIT>,
+        #[serde(with = "seq_of_str")]
+        pub balances: List<Gwei, VALIDATOR_REGISTRY_LIMIT>,
+        pub randao_mixes: Vector<Bytes32, EPOCHS_PER_HISTORICAL_VECTOR>,
+        #[serde(with = "seq_of_str")]
+        pub slashings: Vector<Gwei, EPOCHS_PER_SLASHINGS_VECTOR>,
+        #[serde(with = "seq_of_str")]
+        pub previous_epoch_participation: List<ParticipationFlags, VALIDATOR_REGISTRY_LIMIT>,
+        #[serde(with = "seq_of_str")]
+        pub current_epoch_participation: List<ParticipationFlags, VALIDATOR_REGISTRY_LIMIT>,
+        pub justification_bits: Bitvector<JUSTIFICATION_BITS_LENGTH>,
+        pub previous_justified_checkpoint: Checkpoint,
+        pub current_justified_checkpoint: Checkpoint,
+        pub finalized_checkpoint: Checkpoint,
+        #[serde(with = "seq_of_str")]
+        pub inactivity_scores: List<u64, VALIDATOR_REGISTRY_LIMIT>,
+        pub current_sync_committee: SyncCommittee<SYNC_COMMITTEE_SIZE>,
+        pub next_sync_committee: SyncCommittee<SYNC_COMMITTEE_SIZE>,
+        // Note this is using a different container than the spec. This is to support deserialization from the beacon API
+        // from quicknode which is returning Daneb format containers for this field for some reason..
+        pub latest_execution_payload_header: ethereum_consensus::deneb::ExecutionPayloadHeader<
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+        #[serde(with = "as_str")]
+        pub next_withdrawal_index: WithdrawalIndex,
+        #[serde(with = "as_str")]
+        pub next_withdrawal_validator_index: ValidatorIndex,
+        pub historical_summaries: List<HistoricalSummary, HISTORICAL_ROOTS_LIMIT>,
+        #[serde(with = "as_str")]
+        pub deposit_requests_start_index: u64,
+        #[serde(with = "as_str")]
+        pub deposit_balance_to_consume: Gwei,
+        #[serde(with = "as_str")]
+        pub exit_balance_to_consume: Gwei,
+        #[serde(with = "as_str")]
+        pub earliest_exit_epoch: Epoch,
+        #[serde(with = "as_str")]
+        pub consolidation_balance_to_consume: Gwei,
+        #[serde(with = "as_str")]
+        pub earliest_consolidation_epoch: Epoch,
+        pub pending_deposits: List<PendingDeposit, PENDING_DEPOSITS_LIMIT>,
+        pub pending_partial_withdrawals:
+            List<PendingPartialWithdrawal, PENDING_PARTIAL_WITHDRAWALS_LIMIT>,
+        pub pending_consolidations: List<PendingConsolidation, PENDING_CONSOLIDATIONS_LIMIT>,
+        #[serde(with = "seq_of_str")]
+        pub proposer_lookahead: Vector<ValidatorIndex, PROPOSER_LOOKAHEAD_SIZE>,
+    }
+}
+
+/// Errors returned when decoding a [BeaconState] of a given fork
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("failed to deserialize {fork} beacon state from JSON: {source}")]
+    Json {
+        fork: Version,
+        source: serde_json::Error,
+    },
+    #[error("failed to deserialize {fork} beacon state from SSZ: {source}")]
+    Ssz {
+        fork: Version,
+        source: DeserializeError,
+    },
+    #[error("failed to serialize {fork} beacon state to SSZ: {source}")]
+    SerializeSsz {
+        fork: Version,
+        source: SerializeError,
+    },
+    #[error("unknown fork: {0}")]
+    UnknownVersion(String),
 }
 
 #[derive(
@@ -206,6 +724,7 @@ pub enum BeaconState<
     const PENDING_DEPOSITS_LIMIT: usize,
     const PENDING_PARTIAL_WITHDRAWALS_LIMIT: usize,
     const PENDING_CONSOLIDATIONS_LIMIT: usize,
+    const PROPOSER_LOOKAHEAD_SIZE: usize,
 > {
     Phase0(
         phase0::BeaconState<
@@ -290,6 +809,24 @@ pub enum BeaconState<
             PENDING_CONSOLIDATIONS_LIMIT,
         >,
     ),
+    Fulu(
+        fulu::BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            PENDING_DEPOSITS_LIMIT,
+            PENDING_PARTIAL_WITHDRAWALS_LIMIT,
+            PENDING_CONSOLIDATIONS_LIMIT,
+            PROPOSER_LOOKAHEAD_SIZE,
+        >,
+    ),
 }
 
 impl<
@@ -307,6 +844,7 @@ impl<
         const PENDING_DEPOSITS_LIMIT: usize,
         const PENDING_PARTIAL_WITHDRAWALS_LIMIT: usize,
         const PENDING_CONSOLIDATIONS_LIMIT: usize,
+        const PROPOSER_LOOKAHEAD_SIZE: usize,
     >
     BeaconState<
         SLOTS_PER_HISTORICAL_ROOT,
@@ -323,6 +861,7 @@ impl<
         PENDING_DEPOSITS_LIMIT,
         PENDING_PARTIAL_WITHDRAWALS_LIMIT,
         PENDING_CONSOLIDATIONS_LIMIT,
+        PROPOSER_LOOKAHEAD_SIZE,
     >
 {
     pub fn version(&self) -> Version {
@@ -333,6 +872,7 @@ impl<
             Self::Capella(_) => Version::Capella,
             Self::Deneb(_) => Version::Deneb,
             Self::Electra(_) => Version::Electra,
+            Self::Fulu(_) => Version::Fulu,
         }
     }
 
@@ -344,6 +884,7 @@ impl<
             Self::Capella(inner) => inner.genesis_validators_root,
             Self::Deneb(inner) => inner.genesis_validators_root,
             Self::Electra(inner) => inner.genesis_validators_root,
+            Self::Fulu(inner) => inner.genesis_validators_root,
         }
     }
     pub fn genesis_validators_root_mut(&mut self) -> &mut Root {
@@ -354,6 +895,7 @@ impl<
             Self::Capella(inner) => &mut inner.genesis_validators_root,
             Self::Deneb(inner) => &mut inner.genesis_validators_root,
             Self::Electra(inner) => &mut inner.genesis_validators_root,
+            Self::Fulu(inner) => &mut inner.genesis_validators_root,
         }
     }
     pub fn slot(&self) -> Slot {
@@ -364,6 +906,7 @@ impl<
             Self::Capella(inner) => inner.slot,
             Self::Deneb(inner) => inner.slot,
             Self::Electra(inner) => inner.slot,
+            Self::Fulu(inner) => inner.slot,
         }
     }
 
@@ -375,6 +918,7 @@ impl<
             Self::Capella(inner) => &inner.fork,
             Self::Deneb(inner) => &inner.fork,
             Self::Electra(inner) => &inner.fork,
+            Self::Fulu(inner) => &inner.fork,
         }
     }
 
@@ -386,6 +930,46 @@ impl<
             Self::Capella(inner) => &inner.latest_block_header,
             Self::Deneb(inner) => &inner.latest_block_header,
             Self::Electra(inner) => &inner.latest_block_header,
+            Self::Fulu(inner) => &inner.latest_block_header,
+        }
+    }
+
+    /// Hash of the execution block in the latest beacon block. `None` before Bellatrix
+    pub fn latest_execution_block_hash(&self) -> Option<&Hash32> {
+        match self {
+            Self::Phase0(_) => None,
+            Self::Altair(_) => None,
+            Self::Bellatrix(inner) => Some(&inner.latest_execution_payload_header.block_hash),
+            Self::Capella(inner) => Some(&inner.latest_execution_payload_header.block_hash),
+            Self::Deneb(inner) => Some(&inner.latest_execution_payload_header.block_hash),
+            Self::Electra(inner) => Some(&inner.latest_execution_payload_header.block_hash),
+            Self::Fulu(inner) => Some(&inner.latest_execution_payload_header.block_hash),
+        }
+    }
+
+    /// Number of the execution block in the latest beacon block. `None` before Bellatrix
+    pub fn latest_execution_block_number(&self) -> Option<u64> {
+        match self {
+            Self::Phase0(_) => None,
+            Self::Altair(_) => None,
+            Self::Bellatrix(inner) => Some(inner.latest_execution_payload_header.block_number),
+            Self::Capella(inner) => Some(inner.latest_execution_payload_header.block_number),
+            Self::Deneb(inner) => Some(inner.latest_execution_payload_header.block_number),
+            Self::Electra(inner) => Some(inner.latest_execution_payload_header.block_number),
+            Self::Fulu(inner) => Some(inner.latest_execution_payload_header.block_number),
+        }
+    }
+
+    /// Timestamp of the execution block in the latest beacon block. `None` before Bellatrix
+    pub fn latest_execution_timestamp(&self) -> Option<u64> {
+        match self {
+            Self::Phase0(_) => None,
+            Self::Altair(_) => None,
+            Self::Bellatrix(inner) => Some(inner.latest_execution_payload_header.timestamp),
+            Self::Capella(inner) => Some(inner.latest_execution_payload_header.timestamp),
+            Self::Deneb(inner) => Some(inner.latest_execution_payload_header.timestamp),
+            Self::Electra(inner) => Some(inner.latest_execution_payload_header.timestamp),
+            Self::Fulu(inner) => Some(inner.latest_execution_payload_header.timestamp),
         }
     }
 
@@ -397,6 +981,7 @@ impl<
             Self::Capella(inner) => &inner.block_roots,
             Self::Deneb(inner) => &inner.block_roots,
             Self::Electra(inner) => &inner.block_roots,
+            Self::Fulu(inner) => &inner.block_roots,
         }
     }
 
@@ -408,6 +993,7 @@ impl<
             Self::Capella(inner) => &inner.state_roots,
             Self::Deneb(inner) => &inner.state_roots,
             Self::Electra(inner) => &inner.state_roots,
+            Self::Fulu(inner) => &inner.state_roots,
         }
     }
 
@@ -419,6 +1005,7 @@ impl<
             Self::Capella(inner) => &inner.historical_roots,
             Self::Deneb(inner) => &inner.historical_roots,
             Self::Electra(inner) => &inner.historical_roots,
+            Self::Fulu(inner) => &inner.historical_roots,
         }
     }
     pub fn historical_roots_mut(&mut self) -> &mut List<Root, HISTORICAL_ROOTS_LIMIT> {
@@ -429,6 +1016,7 @@ impl<
             Self::Capella(inner) => &mut inner.historical_roots,
             Self::Deneb(inner) => &mut inner.historical_roots,
             Self::Electra(inner) => &mut inner.historical_roots,
+            Self::Fulu(inner) => &mut inner.historical_roots,
         }
     }
     pub fn validators(&self) -> &List<Validator, VALIDATOR_REGISTRY_LIMIT> {
@@ -439,6 +1027,7 @@ impl<
             Self::Capella(inner) => &inner.validators,
             Self::Deneb(inner) => &inner.validators,
             Self::Electra(inner) => &inner.validators,
+            Self::Fulu(inner) => &inner.validators,
         }
     }
     pub fn balances(&self) -> &List<Gwei, VALIDATOR_REGISTRY_LIMIT> {
@@ -449,6 +1038,7 @@ impl<
             Self::Capella(inner) => &inner.balances,
             Self::Deneb(inner) => &inner.balances,
             Self::Electra(inner) => &inner.balances,
+            Self::Fulu(inner) => &inner.balances,
         }
     }
     pub fn randao_mixes(&self) -> &Vector<Bytes32, EPOCHS_PER_HISTORICAL_VECTOR> {
@@ -459,6 +1049,7 @@ impl<
             Self::Capella(inner) => &inner.randao_mixes,
             Self::Deneb(inner) => &inner.randao_mixes,
             Self::Electra(inner) => &inner.randao_mixes,
+            Self::Fulu(inner) => &inner.randao_mixes,
         }
     }
     pub fn previous_epoch_attestations(
@@ -472,6 +1063,7 @@ impl<
             Self::Capella(_) => None,
             Self::Deneb(_) => None,
             Self::Electra(_) => None,
+            Self::Fulu(_) => None,
         }
     }
     pub fn current_epoch_attestations(
@@ -485,6 +1077,7 @@ impl<
             Self::Capella(_) => None,
             Self::Deneb(_) => None,
             Self::Electra(_) => None,
+            Self::Fulu(_) => None,
         }
     }
     pub fn justification_bits(&self) -> &Bitvector<JUSTIFICATION_BITS_LENGTH> {
@@ -495,6 +1088,7 @@ impl<
             Self::Capella(inner) => &inner.justification_bits,
             Self::Deneb(inner) => &inner.justification_bits,
             Self::Electra(inner) => &inner.justification_bits,
+            Self::Fulu(inner) => &inner.justification_bits,
         }
     }
     pub fn previous_justified_checkpoint(&self) -> &Checkpoint {
@@ -505,6 +1099,7 @@ impl<
             Self::Capella(inner) => &inner.previous_justified_checkpoint,
             Self::Deneb(inner) => &inner.previous_justified_checkpoint,
             Self::Electra(inner) => &inner.previous_justified_checkpoint,
+            Self::Fulu(inner) => &inner.previous_justified_checkpoint,
         }
     }
     pub fn current_justified_checkpoint(&self) -> &Checkpoint {
@@ -515,6 +1110,7 @@ impl<
             Self::Capella(inner) => &inner.current_justified_checkpoint,
             Self::Deneb(inner) => &inner.current_justified_checkpoint,
             Self::Electra(inner) => &inner.current_justified_checkpoint,
+            Self::Fulu(inner) => &inner.current_justified_checkpoint,
         }
     }
     pub fn finalized_checkpoint(&self) -> &Checkpoint {
@@ -525,6 +1121,7 @@ impl<
             Self::Capella(inner) => &inner.finalized_checkpoint,
             Self::Deneb(inner) => &inner.finalized_checkpoint,
             Self::Electra(inner) => &inner.finalized_checkpoint,
+            Self::Fulu(inner) => &inner.finalized_checkpoint,
         }
     }
     pub fn previous_epoch_participation(
@@ -537,6 +1134,7 @@ impl<
             Self::Capella(inner) => Some(&inner.previous_epoch_participation),
             Self::Deneb(inner) => Some(&inner.previous_epoch_participation),
             Self::Electra(inner) => Some(&inner.previous_epoch_participation),
+            Self::Fulu(inner) => Some(&inner.previous_epoch_participation),
         }
     }
     pub fn current_epoch_participation(
@@ -549,12 +1147,51 @@ impl<
             Self::Capella(inner) => Some(&inner.current_epoch_participation),
             Self::Deneb(inner) => Some(&inner.current_epoch_participation),
             Self::Electra(inner) => Some(&inner.current_epoch_participation),
+            Self::Fulu(inner) => Some(&inner.current_epoch_participation),
+        }
+    }
+    pub fn pending_deposits(
+        &self,
+    ) -> Option<&List<electra::PendingDeposit, PENDING_DEPOSITS_LIMIT>> {
+        match self {
+            Self::Phase0(_) => None,
+            Self::Altair(_) => None,
+            Self::Bellatrix(_) => None,
+            Self::Capella(_) => None,
+            Self::Deneb(_) => None,
+            Self::Electra(inner) => Some(&inner.pending_deposits),
+            Self::Fulu(inner) => Some(&inner.pending_deposits),
+        }
+    }
+    pub fn pending_partial_withdrawals(
+        &self,
+    ) -> Option<&List<electra::PendingPartialWithdrawal, PENDING_PARTIAL_WITHDRAWALS_LIMIT>> {
+        match self {
+            Self::Phase0(_) => None,
+            Self::Altair(_) => None,
+            Self::Bellatrix(_) => None,
+            Self::Capella(_) => None,
+            Self::Deneb(_) => None,
+            Self::Electra(inner) => Some(&inner.pending_partial_withdrawals),
+            Self::Fulu(inner) => Some(&inner.pending_partial_withdrawals),
+        }
+    }
+    pub fn pending_consolidations(
+        &self,
+    ) -> Option<&List<electra::PendingConsolidation, PENDING_CONSOLIDATIONS_LIMIT>> {
+        match self {
+            Self::Phase0(_) => None,
+            Self::Altair(_) => None,
+            Self::Bellatrix(_) => None,
+            Self::Capella(_) => None,
+            Self::Deneb(_) => None,
+            Self::Electra(inner) => Some(&inner.pending_consolidations),
+            Self::Fulu(inner) => Some(&inner.pending_consolidations),
         }
     }
 }
 
 impl<
-        'de,
         const SLOTS_PER_HISTORICAL_ROOT: usize,
         const HISTORICAL_ROOTS_LIMIT: usize,
         const ETH1_DATA_VOTES_BOUND: usize,
@@ -569,8 +1206,9 @@ impl<
         const PENDING_DEPOSITS_LIMIT: usize,
         const PENDING_PARTIAL_WITHDRAWALS_LIMIT: usize,
         const PENDING_CONSOLIDATIONS_LIMIT: usize,
-    > serde::Deserialize<'de>
-    for BeaconState<
+        const PROPOSER_LOOKAHEAD_SIZE: usize,
+    >
+    BeaconState<
         SLOTS_PER_HISTORICAL_ROOT,
         HISTORICAL_ROOTS_LIMIT,
         ETH1_DATA_VOTES_BOUND,
@@ -585,38 +1223,165 @@ impl<
         PENDING_DEPOSITS_LIMIT,
         PENDING_PARTIAL_WITHDRAWALS_LIMIT,
         PENDING_CONSOLIDATIONS_LIMIT,
+        PROPOSER_LOOKAHEAD_SIZE,
     >
 {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: serde::Deserializer<'de>,
-    {
-        let value = serde_json::Value::deserialize(deserializer)?;
-        match <_ as serde::Deserialize>::deserialize(&value) {
-            Ok(inner) => {
-                return Ok(Self::Electra(inner));
-            }
-            Err(e) => {
-                eprintln!("Failed to deserialize Electra: {:?}", e);
-            }
-        }
-        if let Ok(inner) = <_ as serde::Deserialize>::deserialize(&value) {
-            return Ok(Self::Deneb(inner));
-        }
-        if let Ok(inner) = <_ as serde::Deserialize>::deserialize(&value) {
-            return Ok(Self::Capella(inner));
-        }
-        if let Ok(inner) = <_ as serde::Deserialize>::deserialize(&value) {
-            return Ok(Self::Bellatrix(inner));
+    /// Deserializes the JSON representation of a beacon state of the given fork,
+    /// e.g. the `data` field of the beacon API state response
+    pub fn from_versioned_json(fork: Version, value: serde_json::Value) -> Result<Self, Error> {
+        let json_error = |source| Error::Json { fork, source };
+        match fork {
+            Version::Phase0 => serde_json::from_value(value)
+                .map(Self::Phase0)
+                .map_err(json_error),
+            Version::Altair => serde_json::from_value(value)
+                .map(Self::Altair)
+                .map_err(json_error),
+            Version::Bellatrix => serde_json::from_value(value)
+                .map(Self::Bellatrix)
+                .map_err(json_error),
+            Version::Capella => serde_json::from_value(value)
+                .map(Self::Capella)
+                .map_err(json_error),
+            Version::Deneb => serde_json::from_value(value)
+                .map(Self::Deneb)
+                .map_err(json_error),
+            Version::Electra => serde_json::from_value(value)
+                .map(Self::Electra)
+                .map_err(json_error),
+            Version::Fulu => serde_json::from_value(value)
+                .map(Self::Fulu)
+                .map_err(json_error),
         }
-        if let Ok(inner) = <_ as serde::Deserialize>::deserialize(&value) {
-            return Ok(Self::Altair(inner));
-        }
-        if let Ok(inner) = <_ as serde::Deserialize>::deserialize(&value) {
-            return Ok(Self::Phase0(inner));
+    }
+
+    /// Deserializes the SSZ encoding of a beacon state of the given fork
+    pub fn from_versioned_ssz(fork: Version, bytes: &[u8]) -> Result<Self, Error> {
+        let ssz_error = |source| Error::Ssz { fork, source };
+        match fork {
+            Version::Phase0 => deserialize(bytes).map(Self::Phase0).map_err(ssz_error),
+            Version::Altair => deserialize(bytes).map(Self::Altair).map_err(ssz_error),
+            Version::Bellatrix => deserialize(bytes).map(Self::Bellatrix).map_err(ssz_error),
+            Version::Capella => deserialize(bytes).map(Self::Capella).map_err(ssz_error),
+            Version::Deneb => deserialize(bytes).map(Self::Deneb).map_err(ssz_error),
+            Version::Electra => deserialize(bytes).map(Self::Electra).map_err(ssz_error),
+            Version::Fulu => deserialize(bytes).map(Self::Fulu).map_err(ssz_error),
         }
-        Err(serde::de::Error::custom(
-            "no variant could be deserialized from input",
-        ))
+    }
+
+    /// Serializes the state to SSZ. Its fork is given by [Self::version].
+    pub fn to_ssz(&self) -> Result<Vec<u8>, Error> {
+        let fork = self.version();
+        let result = match self {
+            Self::Phase0(inner) => serialize(inner),
+            Self::Altair(inner) => serialize(inner),
+            Self::Bellatrix(inner) => serialize(inner),
+            Self::Capella(inner) => serialize(inner),
+            Self::Deneb(inner) => serialize(inner),
+            Self::Electra(inner) => serialize(inner),
+            Self::Fulu(inner) => serialize(inner),
+        };
+        result.map_err(|source| Error::SerializeSsz { fork, source })
     }
 }
+
+/// A [BeaconState] of one consensus preset, see [mainnet::BeaconState] and [minimal::BeaconState].
+/// Hosts that serve networks of either preset are generic over this and pick the implementation
+/// from the preset of the network.
+pub trait PresetBeaconState: HashTreeRoot + Clone + Send + Sync + 'static {
+    const SLOTS_PER_EPOCH: u64;
+
+    /// HistoricalBatch container of the preset
+    type HistoricalBatch: SimpleSerialize + std::fmt::Debug + Send + Sync;
+
+    fn version(&self) -> Version;
+    fn slot(&self) -> Slot;
+    fn latest_block_header(&self) -> &BeaconBlockHeader;
+    fn latest_execution_block_hash(&self) -> Option<&Hash32>;
+    fn latest_execution_block_number(&self) -> Option<u64>;
+    fn latest_execution_timestamp(&self) -> Option<u64>;
+    fn validators(&self) -> &[Validator];
+    fn pending_deposits(&self) -> Option<&[electra::PendingDeposit]>;
+    fn pending_partial_withdrawals(&self) -> Option<&[electra::PendingPartialWithdrawal]>;
+    fn pending_consolidations(&self) -> Option<&[electra::PendingConsolidation]>;
+
+    /// The block and state roots of the state, as summarized into historical_summaries
+    fn historical_batch(&self) -> Self::HistoricalBatch;
+
+    fn from_versioned_json(fork: Version, value: serde_json::Value) -> Result<Self, Error>;
+    fn from_versioned_ssz(fork: Version, bytes: &[u8]) -> Result<Self, Error>;
+    fn to_ssz(&self) -> Result<Vec<u8>, Error>;
+}
+
+macro_rules! impl_preset_beacon_state {
+    ($preset:ident) => {
+        impl PresetBeaconState for $preset::BeaconState {
+            const SLOTS_PER_EPOCH: u64 = phase0::$preset::SLOTS_PER_EPOCH;
+
+            type HistoricalBatch = phase0::$preset::HistoricalBatch;
+
+            fn version(&self) -> Version {
+                self.version()
+            }
+
+            fn slot(&self) -> Slot {
+                self.slot()
+            }
+
+            fn latest_block_header(&self) -> &BeaconBlockHeader {
+                self.latest_block_header()
+            }
+
+            fn latest_execution_block_hash(&self) -> Option<&Hash32> {
+                self.latest_execution_block_hash()
+            }
+
+            fn latest_execution_block_number(&self) -> Option<u64> {
+                self.latest_execution_block_number()
+            }
+
+            fn latest_execution_timestamp(&self) -> Option<u64> {
+                self.latest_execution_timestamp()
+            }
+
+            fn validators(&self) -> &[Validator] {
+                self.validators().as_slice()
+            }
+
+            fn pending_deposits(&self) -> Option<&[electra::PendingDeposit]> {
+                self.pending_deposits().map(|list| list.as_slice())
+            }
+
+            fn pending_partial_withdrawals(&self) -> Option<&[electra::PendingPartialWithdrawal]> {
+                self.pending_partial_withdrawals()
+                    .map(|list| list.as_slice())
+            }
+
+            fn pending_consolidations(&self) -> Option<&[electra::PendingConsolidation]> {
+                self.pending_consolidations().map(|list| list.as_slice())
+            }
+
+            fn historical_batch(&self) -> Self::HistoricalBatch {
+                phase0::$preset::HistoricalBatch {
+                    block_roots: self.block_roots().clone(),
+                    state_roots: self.state_roots().clone(),
+                }
+            }
+
+            fn from_versioned_json(fork: Version, value: serde_json::Value) -> Result<Self, Error> {
+                $preset::BeaconState::from_versioned_json(fork, value)
+            }
+
+            fn from_versioned_ssz(fork: Version, bytes: &[u8]) -> Result<Self, Error> {
+                $preset::BeaconState::from_versioned_ssz(fork, bytes)
+            }
+
+            fn to_ssz(&self) -> Result<Vec<u8>, Error> {
+                self.to_ssz()
+            }
+        }
+    };
+}
+
+impl_preset_beacon_state!(mainnet);
+impl_preset_beacon_state!(minimal);
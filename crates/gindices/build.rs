@@ -0,0 +1,520 @@
+use ssz_rs::GeneralizedIndexable;
+use ssz_rs::Path;
+use ssz_rs::PathElement;
+use std::env;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+fn main() {
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+
+    // Containers whose layout does not depend on the preset
+    let mut f = create(&out_dir, "gen_beacon_block.rs");
+    write_beacon_block_gindex_fns::<_, ethereum_consensus::phase0::BeaconBlockHeader>(&mut f);
+
+    // mainnet preset
+    let mut f = create(&out_dir, "gen_beacon_block_pre_electra.rs");
+    write_beacon_block_body_gindex_fns::<_, ethereum_consensus::deneb::presets::mainnet::BeaconBlock>(
+        &mut f,
+    );
+
+    let mut f = create(&out_dir, "gen_beacon_block_post_electra.rs");
+    write_beacon_block_body_gindex_fns::<_, beacon_state::mainnet::ElectraBeaconBlock>(&mut f);
+    write_execution_requests_gindex_fns::<_, beacon_state::mainnet::ElectraBeaconBlock>(&mut f);
+
+    let mut f = create(&out_dir, "gen_historical_batch.rs");
+    write_historical_batch_gindex_fns::<
+        _,
+        ethereum_consensus::capella::presets::mainnet::HistoricalBatch,
+    >(&mut f);
+
+    let mut f = create(&out_dir, "gen_pre_electra.rs");
+    // Capella states only differ from Deneb inside the execution payload header, which is not
+    // readable through these tables
+    write_gindex_fns::<_, ethereum_consensus::deneb::presets::mainnet::BeaconState>(&mut f);
+
+    let mut f = create(&out_dir, "gen_post_electra.rs");
+    write_gindex_fns::<_, beacon_state::mainnet::ElectraBeaconState>(&mut f);
+    write_electra_gindex_fns::<_, beacon_state::mainnet::ElectraBeaconState>(&mut f);
+
+    let mut f = create(&out_dir, "gen_post_fulu.rs");
+    write_gindex_fns::<_, beacon_state::mainnet::FuluBeaconState>(&mut f);
+    write_electra_gindex_fns::<_, beacon_state::mainnet::FuluBeaconState>(&mut f);
+    write_fulu_gindex_fns::<_, beacon_state::mainnet::FuluBeaconState>(&mut f);
+
+    // minimal preset
+    let mut f = create(&out_dir, "gen_minimal_beacon_block_pre_electra.rs");
+    write_beacon_block_body_gindex_fns::<_, ethereum_consensus::deneb::presets::minimal::BeaconBlock>(
+        &mut f,
+    );
+
+    let mut f = create(&out_dir, "gen_minimal_beacon_block_post_electra.rs");
+    write_beacon_block_body_gindex_fns::<_, beacon_state::minimal::ElectraBeaconBlock>(&mut f);
+    write_execution_requests_gindex_fns::<_, beacon_state::minimal::ElectraBeaconBlock>(&mut f);
+
+    let mut f = create(&out_dir, "gen_minimal_historical_batch.rs");
+    write_historical_batch_gindex_fns::<
+        _,
+        ethereum_consensus::capella::presets::minimal::HistoricalBatch,
+    >(&mut f);
+
+    let mut f = create(&out_dir, "gen_minimal_pre_electra.rs");
+    write_gindex_fns::<_, ethereum_consensus::deneb::presets::minimal::BeaconState>(&mut f);
+
+    let mut f = create(&out_dir, "gen_minimal_post_electra.rs");
+    write_gindex_fns::<_, beacon_state::minimal::ElectraBeaconState>(&mut f);
+    write_electra_gindex_fns::<_, beacon_state::minimal::ElectraBeaconState>(&mut f);
+
+    let mut f = create(&out_dir, "gen_minimal_post_fulu.rs");
+    write_gindex_fns::<_, beacon_state::minimal::FuluBeaconState>(&mut f);
+    write_electra_gindex_fns::<_, beacon_state::minimal::FuluBeaconState>(&mut f);
+    write_fulu_gindex_fns::<_, beacon_state::minimal::FuluBeaconState>(&mut f);
+
+    println!("cargo::rerun-if-changed=build.rs");
+}
+
+fn create(out_dir: &std::path::Path, file_name: &str) -> File {
+    File::create(out_dir.join(file_name)).unwrap()
+}
+
+fn write_beacon_block_gindex_fns<W, G>(w: &mut W)
+where
+    W: Write,
+    G: GeneralizedIndexable,
+{
+    for (name, path) in [
+        ("slot", Path::from(&["slot".into()])),
+        ("state_root", Path::from(&["state_root".into()])),
+    ] {
+        let gindex = G::generalized_index(path).unwrap() as u64;
+
+        w.write_fmt(format_args!(
+            "pub fn {:}() -> u64 {{ {:} }}\n",
+            name, gindex
+        ))
+        .unwrap();
+    }
+}
+
+/// Paths into the body of a full BeaconBlock from the Deneb fork onwards
+fn write_beacon_block_body_gindex_fns<W, G>(w: &mut W)
+where
+    W: Write,
+    G: GeneralizedIndexable,
+{
+    for (name, path) in [
+        ("body", Path::from(&["body".into()])),
+        (
+            "execution_payload",
+            Path::from(&["body".into(), "execution_payload".into()]),
+        ),
+        (
+            "execution_payload_block_number",
+            Path::from(&[
+                "body".into(),
+                "execution_payload".into(),
+                "block_number".into(),
+            ]),
+        ),
+        (
+            "execution_payload_timestamp",
+            Path::from(&[
+                "body".into(),
+                "execution_payload".into(),
+                "timestamp".into(),
+            ]),
+        ),
+        (
+            "execution_payload_block_hash",
+            Path::from(&[
+                "body".into(),
+                "execution_payload".into(),
+                "block_hash".into(),
+            ]),
+        ),
+        (
+            "withdrawals_count",
+            Path::from(&[
+                "body".into(),
+                "execution_payload".into(),
+                "withdrawals".into(),
+                PathElement::Length,
+            ]),
+        ),
+        (
+            "withdrawal_index_base",
+            Path::from(&[
+                "body".into(),
+                "execution_payload".into(),
+                "withdrawals".into(),
+                0.into(),
+                "index".into(),
+            ]),
+        ),
+        (
+            "withdrawal_validator_index_base",
+            Path::from(&[
+                "body".into(),
+                "execution_payload".into(),
+                "withdrawals".into(),
+                0.into(),
+                "validator_index".into(),
+            ]),
+        ),
+        (
+            "withdrawal_address_base",
+            Path::from(&[
+                "body".into(),
+                "execution_payload".into(),
+                "withdrawals".into(),
+                0.into(),
+                "address".into(),
+            ]),
+        ),
+        (
+            "withdrawal_amount_base",
+            Path::from(&[
+                "body".into(),
+                "execution_payload".into(),
+                "withdrawals".into(),
+                0.into(),
+                "amount".into(),
+            ]),
+        ),
+    ] {
+        let gindex = G::generalized_index(path).unwrap() as u64;
+
+        w.write_fmt(format_args!(
+            "pub fn {:}() -> u64 {{ {:} }}\n",
+            name, gindex
+        ))
+        .unwrap();
+    }
+}
+
+/// Paths into the execution requests of a BeaconBlock from the Electra fork onwards
+fn write_execution_requests_gindex_fns<W, G>(w: &mut W)
+where
+    W: Write,
+    G: GeneralizedIndexable,
+{
+    let requests = |list: &str, field: Option<&str>| {
+        let mut path = vec!["body".into(), "execution_requests".into(), list.into()];
+        match field {
+            Some(field) => path.extend([0.into(), field.into()]),
+            None => path.push(PathElement::Length),
+        }
+        path
+    };
+
+    for (name, path) in [
+        (
+            "execution_requests",
+            vec!["body".into(), "execution_requests".into()],
+        ),
+        ("deposit_requests_count", requests("deposits", None)),
+        (
+            "deposit_request_pubkey_base",
+            requests("deposits", Some("public_key")),
+        ),
+        (
+            "deposit_request_withdrawal_credentials_base",
+            requests("deposits", Some("withdrawal_credentials")),
+        ),
+        (
+            "deposit_request_amount_base",
+            requests("deposits", Some("amount")),
+        ),
+        (
+            "deposit_request_signature_base",
+            requests("deposits", Some("signature")),
+        ),
+        (
+            "deposit_request_index_base",
+            requests("deposits", Some("index")),
+        ),
+        ("withdrawal_requests_count", requests("withdrawals", None)),
+        (
+            "withdrawal_request_source_address_base",
+            requests("withdrawals", Some("source_address")),
+        ),
+        (
+            "withdrawal_request_validator_pubkey_base",
+            requests("withdrawals", Some("validator_public_key")),
+        ),
+        (
+            "withdrawal_request_amount_base",
+            requests("withdrawals", Some("amount")),
+        ),
+        (
+            "consolidation_requests_count",
+            requests("consolidations", None),
+        ),
+        (
+            "consolidation_request_source_address_base",
+            requests("consolidations", Some("source_address")),
+        ),
+        (
+            "consolidation_request_source_pubkey_base",
+            requests("consolidations", Some("source_public_key")),
+        ),
+        (
+            "consolidation_request_target_pubkey_base",
+            requests("consolidations", Some("target_public_key")),
+        ),
+    ] {
+        let gindex = G::generalized_index(&path).unwrap() as u64;
+
+        w.write_fmt(format_args!(
+            "pub fn {:}() -> u64 {{ {:} }}\n",
+            name, gindex
+        ))
+        .unwrap();
+    }
+}
+
+fn write_historical_batch_gindex_fns<W, G>(w: &mut W)
+where
+    W: Write,
+    G: GeneralizedIndexable,
+{
+    for (name, path) in [(
+        "state_roots_base",
+        Path::from(&["state_roots".into(), 0.into()]),
+    )] {
+        let gindex = G::generalized_index(path).unwrap() as u64;
+
+        w.write_fmt(format_args!(
+            "pub fn {:}() -> u64 {{ {:} }}\n",
+            name, gindex
+        ))
+        .unwrap();
+    }
+}
+
+/// Paths that only exist from the Fulu fork onwards
+fn write_fulu_gindex_fns<W, G>(w: &mut W)
+where
+    W: Write,
+    G: GeneralizedIndexable,
+{
+    for (name, path) in [(
+        "proposer_lookahead_base",
+        Path::from(&["proposer_lookahead".into(), 0.into()]),
+    )] {
+        let gindex = G::generalized_index(path).unwrap() as u64;
+
+        w.write_fmt(format_args!(
+            "pub fn {:}() -> u64 {{ {:} }}\n",
+            name, gindex
+        ))
+        .unwrap();
+    }
+}
+
+/// Paths that only exist from the Electra fork onwards
+fn write_electra_gindex_fns<W, G>(w: &mut W)
+where
+    W: Write,
+    G: GeneralizedIndexable,
+{
+    for (name, path) in [
+        (
+            "pending_deposits_count",
+            Path::from(&["pending_deposits".into(), PathElement::Length]),
+        ),
+        (
+            "pending_deposit_pubkey_base",
+            Path::from(&["pending_deposits".into(), 0.into(), "public_key".into()]),
+        ),
+        (
+            "pending_deposit_withdrawal_credentials_base",
+            Path::from(&[
+                "pending_deposits".into(),
+                0.into(),
+                "withdrawal_credentials".into(),
+            ]),
+        ),
+        (
+            "pending_deposit_amount_base",
+            Path::from(&["pending_deposits".into(), 0.into(), "amount".into()]),
+        ),
+        (
+            "pending_deposit_signature_base",
+            Path::from(&["pending_deposits".into(), 0.into(), "signature".into()]),
+        ),
+        (
+            "pending_deposit_slot_base",
+            Path::from(&["pending_deposits".into(), 0.into(), "slot".into()]),
+        ),
+        (
+            "pending_partial_withdrawals_count",
+            Path::from(&["pending_partial_withdrawals".into(), PathElement::Length]),
+        ),
+        (
+            "pending_partial_withdrawal_validator_index_base",
+            Path::from(&[
+                "pending_partial_withdrawals".into(),
+                0.into(),
+                "validator_index".into(),
+            ]),
+        ),
+        (
+            "pending_partial_withdrawal_amount_base",
+            Path::from(&[
+                "pending_partial_withdrawals".into(),
+                0.into(),
+                "amount".into(),
+            ]),
+        ),
+        (
+            "pending_partial_withdrawal_withdrawable_epoch_base",
+            Path::from(&[
+                "pending_partial_withdrawals".into(),
+                0.into(),
+                "withdrawable_epoch".into(),
+            ]),
+        ),
+        (
+            "pending_consolidations_count",
+            Path::from(&["pending_consolidations".into(), PathElement::Length]),
+        ),
+        (
+            "pending_consolidation_source_index_base",
+            Path::from(&[
+                "pending_consolidations".into(),
+                0.into(),
+                "source_index".into(),
+            ]),
+        ),
+        (
+            "pending_consolidation_target_index_base",
+            Path::from(&[
+                "pending_consolidations".into(),
+                0.into(),
+                "target_index".into(),
+            ]),
+        ),
+    ] {
+        let gindex = G::generalized_index(path).unwrap() as u64;
+
+        w.write_fmt(format_args!(
+            "pub fn {:}() -> u64 {{ {:} }}\n",
+            name, gindex
+        ))
+        .unwrap();
+    }
+}
+
+fn write_gindex_fns<W, G>(w: &mut W)
+where
+    W: Write,
+    G: GeneralizedIndexable,
+{
+    // Static paths for the BeaconState
+    for (name, path) in [
+        ("slot", Path::from(&["slot".into()])),
+        (
+            "validator_count",
+            Path::from(&["validators".into(), PathElement::Length]),
+        ),
+        (
+            "state_roots_base",
+            Path::from(&["state_roots".into(), 0.into()]),
+        ),
+        (
+            "historical_summaries_base",
+            Path::from(&["historical_summaries".into(), 0.into()]),
+        ),
+        (
+            "validator_balance_base",
+            Path::from(&["balances".into(), 0.into()]),
+        ),
+        (
+            "validator_pubkey_base",
+            Path::from(&["validators".into(), 0.into(), "public_key".into()]),
+        ),
+        (
+            "validator_withdrawal_credentials_base",
+            Path::from(&[
+                "validators".into(),
+                0.into(),
+                "withdrawal_credentials".into(),
+            ]),
+        ),
+        (
+            "validator_effective_balance_base",
+            Path::from(&["validators".into(), 0.into(), "effective_balance".into()]),
+        ),
+        (
+            "validator_activation_eligibility_epoch_base",
+            Path::from(&[
+                "validators".into(),
+                0.into(),
+                "activation_eligibility_epoch".into(),
+            ]),
+        ),
+        (
+            "validator_activation_epoch_base",
+            Path::from(&["validators".into(), 0.into(), "activation_epoch".into()]),
+        ),
+        (
+            "validator_exit_epoch_base",
+            Path::from(&["validators".into(), 0.into(), "exit_epoch".into()]),
+        ),
+        (
+            "validator_slashed_base",
+            Path::from(&["validators".into(), 0.into(), "slashed".into()]),
+        ),
+        (
+            "validator_withdrawable_epoch_base",
+            Path::from(&["validators".into(), 0.into(), "withdrawable_epoch".into()]),
+        ),
+        (
+            "finalized_checkpoint_epoch",
+            Path::from(&["finalized_checkpoint".into(), "epoch".into()]),
+        ),
+        (
+            "finalized_checkpoint_root",
+            Path::from(&["finalized_checkpoint".into(), "root".into()]),
+        ),
+        (
+            "latest_execution_payload_header",
+            Path::from(&["latest_execution_payload_header".into()]),
+        ),
+        (
+            "latest_execution_payload_header_state_root",
+            Path::from(&[
+                "latest_execution_payload_header".into(),
+                "state_root".into(),
+            ]),
+        ),
+        (
+            "latest_execution_payload_header_block_number",
+            Path::from(&[
+                "latest_execution_payload_header".into(),
+                "block_number".into(),
+            ]),
+        ),
+        (
+            "latest_execution_payload_header_timestamp",
+            Path::from(&["latest_execution_payload_header".into(), "timestamp".into()]),
+        ),
+        (
+            "latest_execution_payload_header_block_hash",
+            Path::from(&[
+                "latest_execution_payload_header".into(),
+                "block_hash".into(),
+            ]),
+        ),
+    ] {
+        let gindex = G::generalized_index(path).unwrap() as u64;
+
+        w.write_fmt(format_args!(
+            "pub fn {:}() -> u64 {{ {:} }}\n",
+            name, gindex
+        ))
+        .unwrap();
+    }
+}
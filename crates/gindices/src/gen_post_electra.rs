@@ -1,21 +0,0 @@
-pub fn slot() -> u64 {
-    66
-}
-pub fn validator_count() -> u64 {
-    151
-}
-pub fn state_roots_base() -> u64 {
-    573440
-}
-pub fn historical_summaries_base() -> u64 {
-    3053453312
-}
-pub fn validator_balance_base() -> u64 {
-    41781441855488
-}
-pub fn validator_withdrawal_credentials_base() -> u64 {
-    1319413953331201
-}
-pub fn validator_exit_epoch_base() -> u64 {
-    1319413953331206
-}
@@ -1,21 +0,0 @@
-pub fn slot() -> u64 {
-    34
-}
-pub fn validator_count() -> u64 {
-    87
-}
-pub fn state_roots_base() -> u64 {
-    311296
-}
-pub fn historical_summaries_base() -> u64 {
-    1979711488
-}
-pub fn validator_balance_base() -> u64 {
-    24189255811072
-}
-pub fn validator_withdrawal_credentials_base() -> u64 {
-    756463999909889
-}
-pub fn validator_exit_epoch_base() -> u64 {
-    756463999909894
-}
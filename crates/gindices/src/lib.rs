@@ -1,109 +1,169 @@
-mod gen_post_electra;
-mod gen_pre_electra;
+mod preset;
+
+/// Includes the gindex tables generated by build.rs
+macro_rules! generated {
+    ($($name:ident),* $(,)?) => {
+        $(
+            mod $name {
+                include!(concat!(env!("OUT_DIR"), "/", stringify!($name), ".rs"));
+            }
+        )*
+    };
+}
+
+generated!(
+    gen_beacon_block,
+    gen_beacon_block_pre_electra,
+    gen_beacon_block_post_electra,
+    gen_historical_batch,
+    gen_pre_electra,
+    gen_post_electra,
+    gen_post_fulu,
+    gen_minimal_beacon_block_pre_electra,
+    gen_minimal_beacon_block_post_electra,
+    gen_minimal_historical_batch,
+    gen_minimal_pre_electra,
+    gen_minimal_post_electra,
+    gen_minimal_post_fulu,
+);
+
+pub use preset::{
+    BeaconStateGindices, BeaconStateTable, Layout, Mainnet, Minimal, PendingQueuesTable, Preset,
+};
 
 pub mod presets {
     pub mod mainnet {
 
+        /// The `slot` and `state_root` gindices are shared by the BeaconBlockHeader and the full
+        /// BeaconBlock of every fork. Paths into the body are fork specific.
+        ///
+        /// The execution payload fields of the body are also readable from the latest execution
+        /// payload header of the post-state of the block, which is what the guests prove since
+        /// archived beacon states come without their blocks.
         pub mod beacon_block {
-            pub fn slot() -> u64 {
-                8
-            }
+            pub use crate::gen_beacon_block::*;
 
-            pub fn state_root() -> u64 {
-                11
-            }
-        }
+            /// Deneb blocks. Capella blocks have a smaller execution payload and are not covered.
+            pub mod pre_electra {
+                pub use crate::gen_beacon_block_pre_electra::*;
 
-        pub mod beacon_state {
-            pub const SLOTS_PER_HISTORICAL_ROOT: u64 = 8192;
-            pub const VALIDATOR_REGISTRY_LIMIT: u64 = 1099511627776;
-            pub const CAPELLA_FORK_SLOT: u64 = 6209536;
+                pub fn withdrawal_index(withdrawal_index: u64) -> u64 {
+                    withdrawal_index_base() + withdrawal_index * 4
+                }
+
+                pub fn withdrawal_validator_index(withdrawal_index: u64) -> u64 {
+                    withdrawal_validator_index_base() + withdrawal_index * 4
+                }
 
+                pub fn withdrawal_address(withdrawal_index: u64) -> u64 {
+                    withdrawal_address_base() + withdrawal_index * 4
+                }
+
+                pub fn withdrawal_amount(withdrawal_index: u64) -> u64 {
+                    withdrawal_amount_base() + withdrawal_index * 4
+                }
+            }
+
+            /// Electra and Fulu blocks, which carry the execution layer requests
             pub mod post_electra {
-                use super::*;
-                pub use crate::gen_post_electra::*;
-
-                pub fn state_roots(slot: u64) -> u64 {
-                    // note this only holds if slot < state.slot <= slot + SLOTS_PER_HISTORICAL_ROOT
-                    // otherwise the state_root is not available in the state_roots list
-                    let index = slot % SLOTS_PER_HISTORICAL_ROOT;
-                    state_roots_base() + index
+                pub use crate::gen_beacon_block_post_electra::*;
+
+                pub fn withdrawal_index(withdrawal_index: u64) -> u64 {
+                    withdrawal_index_base() + withdrawal_index * 4
                 }
 
-                pub fn historical_summaries(slot: u64) -> u64 {
-                    assert!(
-                        slot >= CAPELLA_FORK_SLOT,
-                        "Historical summaries are only available from Capella fork onwards"
-                    );
-                    let index = (slot - CAPELLA_FORK_SLOT) / SLOTS_PER_HISTORICAL_ROOT;
-                    historical_summaries_base() + index
+                pub fn withdrawal_validator_index(withdrawal_index: u64) -> u64 {
+                    withdrawal_validator_index_base() + withdrawal_index * 4
                 }
 
-                pub fn validator_balance(validator_index: u64) -> u64 {
-                    validator_balance_base() + (validator_index / 4)
+                pub fn withdrawal_address(withdrawal_index: u64) -> u64 {
+                    withdrawal_address_base() + withdrawal_index * 4
                 }
 
-                pub fn validator_withdrawal_credentials(validator_index: u64) -> u64 {
-                    validator_withdrawal_credentials_base() + validator_index * 8
+                pub fn withdrawal_amount(withdrawal_index: u64) -> u64 {
+                    withdrawal_amount_base() + withdrawal_index * 4
                 }
 
-                pub fn validator_exit_epoch(validator_index: u64) -> u64 {
-                    validator_exit_epoch_base() + validator_index * 8
+                pub fn deposit_request_pubkey(request_index: u64) -> u64 {
+                    deposit_request_pubkey_base() + request_index * 8
                 }
-            }
 
-            pub mod pre_electra {
-                use super::*;
-                pub use crate::gen_pre_electra::*;
-
-                pub fn state_roots(slot: u64) -> u64 {
-                    // note this only holds if slot < state.slot <= slot + SLOTS_PER_HISTORICAL_ROOT
-                    // otherwise the state_root is not available in the state_roots list
-                    let index = slot % SLOTS_PER_HISTORICAL_ROOT;
-                    state_roots_base() + index
+                pub fn deposit_request_withdrawal_credentials(request_index: u64) -> u64 {
+                    deposit_request_withdrawal_credentials_base() + request_index * 8
                 }
 
-                pub fn historical_summaries(slot: u64) -> u64 {
-                    assert!(
-                        slot >= CAPELLA_FORK_SLOT,
-                        "Historical summaries are only available from Capella fork onwards"
-                    );
-                    let index = (slot - CAPELLA_FORK_SLOT) / SLOTS_PER_HISTORICAL_ROOT;
-                    historical_summaries_base() + index
+                pub fn deposit_request_amount(request_index: u64) -> u64 {
+                    deposit_request_amount_base() + request_index * 8
                 }
 
-                pub fn validator_balance(validator_index: u64) -> u64 {
-                    validator_balance_base() + (validator_index / 4)
+                pub fn deposit_request_signature(request_index: u64) -> u64 {
+                    deposit_request_signature_base() + request_index * 8
                 }
 
-                pub fn validator_withdrawal_credentials(validator_index: u64) -> u64 {
-                    validator_withdrawal_credentials_base() + validator_index * 8
+                pub fn deposit_request_index(request_index: u64) -> u64 {
+                    deposit_request_index_base() + request_index * 8
                 }
 
-                pub fn validator_exit_epoch(validator_index: u64) -> u64 {
-                    validator_exit_epoch_base() + validator_index * 8
+                pub fn withdrawal_request_source_address(request_index: u64) -> u64 {
+                    withdrawal_request_source_address_base() + request_index * 4
+                }
+
+                pub fn withdrawal_request_validator_pubkey(request_index: u64) -> u64 {
+                    withdrawal_request_validator_pubkey_base() + request_index * 4
+                }
+
+                pub fn withdrawal_request_amount(request_index: u64) -> u64 {
+                    withdrawal_request_amount_base() + request_index * 4
+                }
+
+                pub fn consolidation_request_source_address(request_index: u64) -> u64 {
+                    consolidation_request_source_address_base() + request_index * 4
+                }
+
+                pub fn consolidation_request_source_pubkey(request_index: u64) -> u64 {
+                    consolidation_request_source_pubkey_base() + request_index * 4
+                }
+
+                pub fn consolidation_request_target_pubkey(request_index: u64) -> u64 {
+                    consolidation_request_target_pubkey_base() + request_index * 4
                 }
             }
         }
 
-        pub mod historical_batch {
-            pub fn state_roots(slot: u64) -> u64 {
-                // note this only holds if slot < state.slot <= slot + SLOTS_PER_HISTORICAL_ROOT
-                // otherwise the state_root is not available in the state_roots list
-                let index = slot % super::beacon_state::SLOTS_PER_HISTORICAL_ROOT;
-                24576 + index
+        /// Use [crate::BeaconStateGindices] with [crate::Mainnet] for the gindices of the BeaconState
+        pub mod beacon_state {
+            pub const SLOTS_PER_HISTORICAL_ROOT: u64 = 8192;
+        }
+    }
+
+    /// The minimal preset used by local devnets. Only the block tables are exposed here,
+    /// use [crate::BeaconStateGindices] with [crate::Minimal] for the BeaconState.
+    pub mod minimal {
+        pub mod beacon_block {
+            pub use crate::gen_beacon_block::*;
+
+            pub mod pre_electra {
+                pub use crate::gen_minimal_beacon_block_pre_electra::*;
+            }
+
+            pub mod post_electra {
+                pub use crate::gen_minimal_beacon_block_post_electra::*;
             }
         }
+
+        pub mod beacon_state {
+            pub const SLOTS_PER_HISTORICAL_ROOT: u64 = 64;
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    // use beacon_state::mainnet::ElectraBeaconState as BeaconState;
-    use presets::mainnet::beacon_state::SLOTS_PER_HISTORICAL_ROOT;
     use ssz_rs::prelude::*;
 
+    const MAINNET_CAPELLA_FORK_SLOT: u64 = 6209536;
+
     #[test]
     fn block_state_root() -> anyhow::Result<()> {
         assert_eq!(
@@ -126,207 +186,754 @@ mod test {
         Ok(())
     }
 
-    mod pre_electra {
+    mod beacon_block_pre_electra {
         use super::*;
-        use ethereum_consensus::capella::presets::mainnet::BeaconState;
+        use ethereum_consensus::capella::mainnet::MAX_WITHDRAWALS_PER_PAYLOAD;
+        use ethereum_consensus::deneb::presets::mainnet::BeaconBlock;
+        use presets::mainnet::beacon_block::pre_electra;
 
         #[test]
-        fn slot() -> anyhow::Result<()> {
+        fn slot_and_state_root() -> anyhow::Result<()> {
+            assert_eq!(
+                BeaconBlock::generalized_index(&["slot".into()])? as u64,
+                presets::mainnet::beacon_block::slot()
+            );
             assert_eq!(
-                BeaconState::generalized_index(&["slot".into(),])? as u64,
-                presets::mainnet::beacon_state::pre_electra::slot()
+                BeaconBlock::generalized_index(&["state_root".into()])? as u64,
+                presets::mainnet::beacon_block::state_root()
             );
             Ok(())
         }
 
         #[test]
-        fn validator_count() -> anyhow::Result<()> {
+        fn execution_payload() -> anyhow::Result<()> {
+            for (field, gindex) in [
+                (
+                    "block_number",
+                    pre_electra::execution_payload_block_number(),
+                ),
+                ("timestamp", pre_electra::execution_payload_timestamp()),
+                ("block_hash", pre_electra::execution_payload_block_hash()),
+            ] {
+                assert_eq!(
+                    BeaconBlock::generalized_index(&[
+                        "body".into(),
+                        "execution_payload".into(),
+                        field.into(),
+                    ])? as u64,
+                    gindex
+                );
+            }
             assert_eq!(
-                BeaconState::generalized_index(&["validators".into(), PathElement::Length,])?
+                BeaconBlock::generalized_index(&["body".into(), "execution_payload".into()])?
                     as u64,
-                presets::mainnet::beacon_state::pre_electra::validator_count()
+                pre_electra::execution_payload()
             );
             Ok(())
         }
 
         #[test]
-        fn state_roots() -> anyhow::Result<()> {
-            for index in 0_usize..presets::mainnet::beacon_state::SLOTS_PER_HISTORICAL_ROOT as usize
-            {
+        fn withdrawals() -> anyhow::Result<()> {
+            let withdrawals = |index: usize, field: &'static str| -> Vec<PathElement> {
+                vec![
+                    "body".into(),
+                    "execution_payload".into(),
+                    "withdrawals".into(),
+                    index.into(),
+                    field.into(),
+                ]
+            };
+            assert_eq!(
+                BeaconBlock::generalized_index(&[
+                    "body".into(),
+                    "execution_payload".into(),
+                    "withdrawals".into(),
+                    PathElement::Length,
+                ])? as u64,
+                pre_electra::withdrawals_count()
+            );
+            for index in 0_usize..MAX_WITHDRAWALS_PER_PAYLOAD {
+                assert_eq!(
+                    BeaconBlock::generalized_index(&withdrawals(index, "index"))? as u64,
+                    pre_electra::withdrawal_index(index as u64)
+                );
                 assert_eq!(
-                    BeaconState::generalized_index(&["state_roots".into(), index.into(),])? as u64,
-                    presets::mainnet::beacon_state::pre_electra::state_roots(index as u64)
+                    BeaconBlock::generalized_index(&withdrawals(index, "validator_index"))? as u64,
+                    pre_electra::withdrawal_validator_index(index as u64)
+                );
+                assert_eq!(
+                    BeaconBlock::generalized_index(&withdrawals(index, "address"))? as u64,
+                    pre_electra::withdrawal_address(index as u64)
+                );
+                assert_eq!(
+                    BeaconBlock::generalized_index(&withdrawals(index, "amount"))? as u64,
+                    pre_electra::withdrawal_amount(index as u64)
                 );
             }
             Ok(())
         }
+    }
+
+    mod beacon_block_post_electra {
+        use super::*;
+        use beacon_state::mainnet::{
+            ElectraBeaconBlock as BeaconBlock, MAX_CONSOLIDATION_REQUESTS_PER_PAYLOAD,
+            MAX_DEPOSIT_REQUESTS_PER_PAYLOAD, MAX_WITHDRAWAL_REQUESTS_PER_PAYLOAD,
+        };
+        use ethereum_consensus::capella::mainnet::MAX_WITHDRAWALS_PER_PAYLOAD;
+        use presets::mainnet::beacon_block::post_electra;
+
+        fn requests(list: &'static str, index: usize, field: &'static str) -> Vec<PathElement> {
+            vec![
+                "body".into(),
+                "execution_requests".into(),
+                list.into(),
+                index.into(),
+                field.into(),
+            ]
+        }
+
+        #[test]
+        fn slot_and_state_root() -> anyhow::Result<()> {
+            assert_eq!(
+                BeaconBlock::generalized_index(&["slot".into()])? as u64,
+                presets::mainnet::beacon_block::slot()
+            );
+            assert_eq!(
+                BeaconBlock::generalized_index(&["state_root".into()])? as u64,
+                presets::mainnet::beacon_block::state_root()
+            );
+            Ok(())
+        }
 
         #[test]
-        fn historical_summaries() -> anyhow::Result<()> {
-            for index in 0_u64..10 {
-                let slot = presets::mainnet::beacon_state::CAPELLA_FORK_SLOT
-                    + (index * SLOTS_PER_HISTORICAL_ROOT);
+        fn execution_payload() -> anyhow::Result<()> {
+            for (field, gindex) in [
+                (
+                    "block_number",
+                    post_electra::execution_payload_block_number(),
+                ),
+                ("timestamp", post_electra::execution_payload_timestamp()),
+                ("block_hash", post_electra::execution_payload_block_hash()),
+            ] {
                 assert_eq!(
-                    BeaconState::generalized_index(&[
-                        "historical_summaries".into(),
-                        (index as usize).into(),
+                    BeaconBlock::generalized_index(&[
+                        "body".into(),
+                        "execution_payload".into(),
+                        field.into(),
                     ])? as u64,
-                    presets::mainnet::beacon_state::pre_electra::historical_summaries(slot as u64)
+                    gindex
                 );
             }
+            assert_eq!(
+                BeaconBlock::generalized_index(&["body".into(), "execution_payload".into()])?
+                    as u64,
+                post_electra::execution_payload()
+            );
             Ok(())
         }
 
         #[test]
-        fn validator_balance() -> anyhow::Result<()> {
-            for index in 0_usize..1_000_000 {
+        fn withdrawals() -> anyhow::Result<()> {
+            let withdrawals = |index: usize, field: &'static str| -> Vec<PathElement> {
+                vec![
+                    "body".into(),
+                    "execution_payload".into(),
+                    "withdrawals".into(),
+                    index.into(),
+                    field.into(),
+                ]
+            };
+            assert_eq!(
+                BeaconBlock::generalized_index(&[
+                    "body".into(),
+                    "execution_payload".into(),
+                    "withdrawals".into(),
+                    PathElement::Length,
+                ])? as u64,
+                post_electra::withdrawals_count()
+            );
+            for index in 0_usize..MAX_WITHDRAWALS_PER_PAYLOAD {
+                assert_eq!(
+                    BeaconBlock::generalized_index(&withdrawals(index, "index"))? as u64,
+                    post_electra::withdrawal_index(index as u64)
+                );
+                assert_eq!(
+                    BeaconBlock::generalized_index(&withdrawals(index, "validator_index"))? as u64,
+                    post_electra::withdrawal_validator_index(index as u64)
+                );
                 assert_eq!(
-                    BeaconState::generalized_index(&["balances".into(), index.into(),])? as u64,
-                    presets::mainnet::beacon_state::pre_electra::validator_balance(index as u64)
+                    BeaconBlock::generalized_index(&withdrawals(index, "address"))? as u64,
+                    post_electra::withdrawal_address(index as u64)
+                );
+                assert_eq!(
+                    BeaconBlock::generalized_index(&withdrawals(index, "amount"))? as u64,
+                    post_electra::withdrawal_amount(index as u64)
                 );
             }
             Ok(())
         }
 
         #[test]
-        fn validator_withdrawal_credential() -> anyhow::Result<()> {
-            for index in 0_usize..1_000_000 {
+        fn request_counts() -> anyhow::Result<()> {
+            for (list, gindex) in [
+                ("deposits", post_electra::deposit_requests_count()),
+                ("withdrawals", post_electra::withdrawal_requests_count()),
+                (
+                    "consolidations",
+                    post_electra::consolidation_requests_count(),
+                ),
+            ] {
                 assert_eq!(
-                    BeaconState::generalized_index(&[
-                        "validators".into(),
-                        index.into(),
-                        "withdrawal_credentials".into(),
+                    BeaconBlock::generalized_index(&[
+                        "body".into(),
+                        "execution_requests".into(),
+                        list.into(),
+                        PathElement::Length,
                     ])? as u64,
-                    presets::mainnet::beacon_state::pre_electra::validator_withdrawal_credentials(
-                        index as u64
-                    )
+                    gindex
                 );
             }
             Ok(())
         }
 
         #[test]
-        fn validator_exit_epoch() -> anyhow::Result<()> {
-            for index in 0_usize..1_000_000 {
-                assert_eq!(
-                    BeaconState::generalized_index(&[
-                        "validators".into(),
-                        index.into(),
-                        "exit_epoch".into(),
-                    ])? as u64,
-                    presets::mainnet::beacon_state::pre_electra::validator_exit_epoch(index as u64)
-                );
+        fn deposit_requests() -> anyhow::Result<()> {
+            for index in 0_usize..MAX_DEPOSIT_REQUESTS_PER_PAYLOAD {
+                let i = index as u64;
+                for (field, gindex) in [
+                    ("public_key", post_electra::deposit_request_pubkey(i)),
+                    (
+                        "withdrawal_credentials",
+                        post_electra::deposit_request_withdrawal_credentials(i),
+                    ),
+                    ("amount", post_electra::deposit_request_amount(i)),
+                    ("signature", post_electra::deposit_request_signature(i)),
+                    ("index", post_electra::deposit_request_index(i)),
+                ] {
+                    assert_eq!(
+                        BeaconBlock::generalized_index(&requests("deposits", index, field))? as u64,
+                        gindex
+                    );
+                }
             }
             Ok(())
         }
-    }
-
-    mod post_electra {
-        use super::*;
-        use beacon_state::mainnet::ElectraBeaconState as BeaconState;
 
         #[test]
-        fn slot() -> anyhow::Result<()> {
-            assert_eq!(
-                BeaconState::generalized_index(&["slot".into(),])? as u64,
-                presets::mainnet::beacon_state::post_electra::slot()
-            );
+        fn withdrawal_requests() -> anyhow::Result<()> {
+            for index in 0_usize..MAX_WITHDRAWAL_REQUESTS_PER_PAYLOAD {
+                let i = index as u64;
+                for (field, gindex) in [
+                    (
+                        "source_address",
+                        post_electra::withdrawal_request_source_address(i),
+                    ),
+                    (
+                        "validator_public_key",
+                        post_electra::withdrawal_request_validator_pubkey(i),
+                    ),
+                    ("amount", post_electra::withdrawal_request_amount(i)),
+                ] {
+                    assert_eq!(
+                        BeaconBlock::generalized_index(&requests("withdrawals", index, field))?
+                            as u64,
+                        gindex
+                    );
+                }
+            }
             Ok(())
         }
 
         #[test]
-        fn validator_count() -> anyhow::Result<()> {
+        fn consolidation_requests() -> anyhow::Result<()> {
+            for index in 0_usize..MAX_CONSOLIDATION_REQUESTS_PER_PAYLOAD {
+                let i = index as u64;
+                for (field, gindex) in [
+                    (
+                        "source_address",
+                        post_electra::consolidation_request_source_address(i),
+                    ),
+                    (
+                        "source_public_key",
+                        post_electra::consolidation_request_source_pubkey(i),
+                    ),
+                    (
+                        "target_public_key",
+                        post_electra::consolidation_request_target_pubkey(i),
+                    ),
+                ] {
+                    assert_eq!(
+                        BeaconBlock::generalized_index(&requests("consolidations", index, field))?
+                            as u64,
+                        gindex
+                    );
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// Checks the gindices that exist in every layout
+    fn check_common<S: GeneralizedIndexable>(
+        gindices: &BeaconStateGindices,
+        capella_fork_slot: u64,
+    ) -> anyhow::Result<()> {
+        assert_eq!(
+            S::generalized_index(&["slot".into()])? as u64,
+            gindices.slot()
+        );
+        assert_eq!(
+            S::generalized_index(&["validators".into(), PathElement::Length])? as u64,
+            gindices.validator_count()
+        );
+        for index in 0_usize..gindices.slots_per_historical_root() as usize {
             assert_eq!(
-                BeaconState::generalized_index(&["validators".into(), PathElement::Length,])?
-                    as u64,
-                presets::mainnet::beacon_state::post_electra::validator_count()
+                S::generalized_index(&["state_roots".into(), index.into()])? as u64,
+                gindices.state_roots(index as u64)
             );
-            Ok(())
         }
+        for index in 0_usize..10 {
+            let slot = capella_fork_slot + (index as u64) * gindices.slots_per_historical_root();
+            assert_eq!(
+                S::generalized_index(&["historical_summaries".into(), index.into()])? as u64,
+                gindices.historical_summaries(slot)
+            );
+        }
+        assert_eq!(
+            S::generalized_index(&["latest_execution_payload_header".into()])? as u64,
+            gindices.latest_execution_payload_header()
+        );
+        for (path, gindex) in [
+            (
+                ["finalized_checkpoint", "epoch"],
+                gindices.finalized_checkpoint_epoch(),
+            ),
+            (
+                ["finalized_checkpoint", "root"],
+                gindices.finalized_checkpoint_root(),
+            ),
+            (
+                ["latest_execution_payload_header", "block_hash"],
+                gindices.latest_execution_payload_header_block_hash(),
+            ),
+            (
+                ["latest_execution_payload_header", "block_number"],
+                gindices.latest_execution_payload_header_block_number(),
+            ),
+            (
+                ["latest_execution_payload_header", "timestamp"],
+                gindices.latest_execution_payload_header_timestamp(),
+            ),
+            (
+                ["latest_execution_payload_header", "state_root"],
+                gindices.latest_execution_payload_header_state_root(),
+            ),
+        ] {
+            assert_eq!(
+                S::generalized_index(&[path[0].into(), path[1].into()])? as u64,
+                gindex
+            );
+        }
+        for index in 0_usize..10_000 {
+            assert_eq!(
+                S::generalized_index(&["balances".into(), index.into()])? as u64,
+                gindices.validator_balance(index as u64)
+            );
+            for (field, gindex) in [
+                ("public_key", gindices.validator_pubkey(index as u64)),
+                (
+                    "effective_balance",
+                    gindices.validator_effective_balance(index as u64),
+                ),
+                (
+                    "activation_eligibility_epoch",
+                    gindices.validator_activation_eligibility_epoch(index as u64),
+                ),
+                (
+                    "activation_epoch",
+                    gindices.validator_activation_epoch(index as u64),
+                ),
+                (
+                    "withdrawal_credentials",
+                    gindices.validator_withdrawal_credentials(index as u64),
+                ),
+                ("slashed", gindices.validator_slashed(index as u64)),
+                ("exit_epoch", gindices.validator_exit_epoch(index as u64)),
+                (
+                    "withdrawable_epoch",
+                    gindices.validator_withdrawable_epoch(index as u64),
+                ),
+            ] {
+                assert_eq!(
+                    S::generalized_index(&["validators".into(), index.into(), field.into()])?
+                        as u64,
+                    gindex
+                );
+            }
+        }
+        Ok(())
+    }
 
-        #[test]
-        fn state_roots() -> anyhow::Result<()> {
-            for index in 0_usize..presets::mainnet::beacon_state::SLOTS_PER_HISTORICAL_ROOT as usize
-            {
+    /// Checks the gindices of the pending queues added in Electra
+    fn check_pending_queues<S: GeneralizedIndexable>(
+        gindices: &BeaconStateGindices,
+    ) -> anyhow::Result<()> {
+        for (list, gindex) in [
+            ("pending_deposits", gindices.pending_deposits_count()),
+            (
+                "pending_partial_withdrawals",
+                gindices.pending_partial_withdrawals_count(),
+            ),
+            (
+                "pending_consolidations",
+                gindices.pending_consolidations_count(),
+            ),
+        ] {
+            assert_eq!(
+                S::generalized_index(&[list.into(), PathElement::Length])? as u64,
+                gindex
+            );
+        }
+        // the minimal preset limits the partial withdrawal and consolidation queues to 64 entries,
+        // so this is a range every preset can index
+        for index in 0_usize..64 {
+            for (list, field, gindex) in [
+                (
+                    "pending_deposits",
+                    "public_key",
+                    gindices.pending_deposit_pubkey(index as u64),
+                ),
+                (
+                    "pending_deposits",
+                    "withdrawal_credentials",
+                    gindices.pending_deposit_withdrawal_credentials(index as u64),
+                ),
+                (
+                    "pending_deposits",
+                    "amount",
+                    gindices.pending_deposit_amount(index as u64),
+                ),
+                (
+                    "pending_deposits",
+                    "signature",
+                    gindices.pending_deposit_signature(index as u64),
+                ),
+                (
+                    "pending_deposits",
+                    "slot",
+                    gindices.pending_deposit_slot(index as u64),
+                ),
+                (
+                    "pending_partial_withdrawals",
+                    "validator_index",
+                    gindices.pending_partial_withdrawal_validator_index(index as u64),
+                ),
+                (
+                    "pending_partial_withdrawals",
+                    "amount",
+                    gindices.pending_partial_withdrawal_amount(index as u64),
+                ),
+                (
+                    "pending_partial_withdrawals",
+                    "withdrawable_epoch",
+                    gindices.pending_partial_withdrawal_withdrawable_epoch(index as u64),
+                ),
+                (
+                    "pending_consolidations",
+                    "source_index",
+                    gindices.pending_consolidation_source_index(index as u64),
+                ),
+                (
+                    "pending_consolidations",
+                    "target_index",
+                    gindices.pending_consolidation_target_index(index as u64),
+                ),
+            ] {
                 assert_eq!(
-                    BeaconState::generalized_index(&["state_roots".into(), index.into(),])? as u64,
-                    presets::mainnet::beacon_state::post_electra::state_roots(index as u64)
+                    S::generalized_index(&[list.into(), index.into(), field.into()])? as u64,
+                    gindex
                 );
             }
-            Ok(())
+        }
+        Ok(())
+    }
+
+    mod mainnet {
+        use super::*;
+
+        #[test]
+        fn pre_electra() -> anyhow::Result<()> {
+            let gindices =
+                BeaconStateGindices::new::<Mainnet>(Layout::PreElectra, MAINNET_CAPELLA_FORK_SLOT);
+            check_common::<ethereum_consensus::deneb::presets::mainnet::BeaconState>(
+                &gindices,
+                MAINNET_CAPELLA_FORK_SLOT,
+            )
+        }
+
+        #[test]
+        fn post_electra() -> anyhow::Result<()> {
+            use beacon_state::mainnet::ElectraBeaconState;
+
+            let gindices =
+                BeaconStateGindices::new::<Mainnet>(Layout::PostElectra, MAINNET_CAPELLA_FORK_SLOT);
+            check_common::<ElectraBeaconState>(&gindices, MAINNET_CAPELLA_FORK_SLOT)?;
+            check_pending_queues::<ElectraBeaconState>(&gindices)
         }
 
         #[test]
-        fn historical_summaries() -> anyhow::Result<()> {
-            for index in 0_u64..10 {
-                let slot = presets::mainnet::beacon_state::CAPELLA_FORK_SLOT
-                    + (index * SLOTS_PER_HISTORICAL_ROOT);
+        fn post_fulu() -> anyhow::Result<()> {
+            use beacon_state::mainnet::{FuluBeaconState, PROPOSER_LOOKAHEAD_SIZE};
+
+            let gindices =
+                BeaconStateGindices::new::<Mainnet>(Layout::PostFulu, MAINNET_CAPELLA_FORK_SLOT);
+            check_common::<FuluBeaconState>(&gindices, MAINNET_CAPELLA_FORK_SLOT)?;
+            check_pending_queues::<FuluBeaconState>(&gindices)?;
+            for index in 0_usize..PROPOSER_LOOKAHEAD_SIZE {
                 assert_eq!(
-                    BeaconState::generalized_index(&[
-                        "historical_summaries".into(),
-                        (index as usize).into(),
+                    FuluBeaconState::generalized_index(&[
+                        "proposer_lookahead".into(),
+                        index.into()
                     ])? as u64,
-                    presets::mainnet::beacon_state::post_electra::historical_summaries(slot as u64)
+                    gindices.proposer_lookahead(index as u64)
                 );
             }
             Ok(())
         }
 
         #[test]
-        fn validator_balance() -> anyhow::Result<()> {
-            for index in 0_usize..1_000_000 {
+        fn historical_batch_state_roots() -> anyhow::Result<()> {
+            use ethereum_consensus::capella::presets::mainnet::HistoricalBatch;
+
+            let gindices =
+                BeaconStateGindices::new::<Mainnet>(Layout::PostElectra, MAINNET_CAPELLA_FORK_SLOT);
+            for index in 0_usize..presets::mainnet::beacon_state::SLOTS_PER_HISTORICAL_ROOT as usize
+            {
                 assert_eq!(
-                    BeaconState::generalized_index(&["balances".into(), index.into(),])? as u64,
-                    presets::mainnet::beacon_state::post_electra::validator_balance(index as u64)
+                    HistoricalBatch::generalized_index(&["state_roots".into(), index.into()])?
+                        as u64,
+                    gindices.historical_batch_state_roots(index as u64)
                 );
             }
             Ok(())
         }
+    }
+
+    mod minimal {
+        use super::*;
+        use ethereum_consensus::capella::presets::minimal::HistoricalBatch;
+
+        #[test]
+        fn pre_electra() -> anyhow::Result<()> {
+            let gindices = BeaconStateGindices::new::<Minimal>(Layout::PreElectra, 0);
+            check_common::<ethereum_consensus::deneb::presets::minimal::BeaconState>(&gindices, 0)
+        }
 
         #[test]
-        fn validator_withdrawal_credential() -> anyhow::Result<()> {
-            for index in 0_usize..1_000_000 {
+        fn post_electra() -> anyhow::Result<()> {
+            let gindices = BeaconStateGindices::new::<Minimal>(Layout::PostElectra, 0);
+            check_common::<beacon_state::minimal::ElectraBeaconState>(&gindices, 0)?;
+            check_pending_queues::<beacon_state::minimal::ElectraBeaconState>(&gindices)
+        }
+
+        #[test]
+        fn post_fulu() -> anyhow::Result<()> {
+            use beacon_state::minimal::{FuluBeaconState, PROPOSER_LOOKAHEAD_SIZE};
+
+            let gindices = BeaconStateGindices::new::<Minimal>(Layout::PostFulu, 0);
+            check_common::<FuluBeaconState>(&gindices, 0)?;
+            check_pending_queues::<FuluBeaconState>(&gindices)?;
+            for index in 0_usize..PROPOSER_LOOKAHEAD_SIZE {
                 assert_eq!(
-                    BeaconState::generalized_index(&[
-                        "validators".into(),
-                        index.into(),
-                        "withdrawal_credentials".into(),
+                    FuluBeaconState::generalized_index(&[
+                        "proposer_lookahead".into(),
+                        index.into()
                     ])? as u64,
-                    presets::mainnet::beacon_state::post_electra::validator_withdrawal_credentials(
-                        index as u64
-                    )
+                    gindices.proposer_lookahead(index as u64)
                 );
             }
             Ok(())
         }
 
         #[test]
-        fn validator_exit_epoch() -> anyhow::Result<()> {
-            for index in 0_usize..1_000_000 {
+        fn beacon_block() -> anyhow::Result<()> {
+            use beacon_state::minimal::ElectraBeaconBlock;
+            use ethereum_consensus::deneb::presets::minimal::BeaconBlock;
+            use presets::minimal::beacon_block::{post_electra, pre_electra};
+
+            let block_hash = [
+                "body".into(),
+                "execution_payload".into(),
+                "block_hash".into(),
+            ];
+            assert_eq!(
+                BeaconBlock::generalized_index(&block_hash)? as u64,
+                pre_electra::execution_payload_block_hash()
+            );
+            assert_eq!(
+                ElectraBeaconBlock::generalized_index(&block_hash)? as u64,
+                post_electra::execution_payload_block_hash()
+            );
+            assert_eq!(
+                ElectraBeaconBlock::generalized_index(&[
+                    "body".into(),
+                    "execution_requests".into(),
+                    "deposits".into(),
+                    PathElement::Length,
+                ])? as u64,
+                post_electra::deposit_requests_count()
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn historical_batch_state_roots() -> anyhow::Result<()> {
+            let gindices = BeaconStateGindices::new::<Minimal>(Layout::PostElectra, 0);
+            for index in 0_usize..presets::minimal::beacon_state::SLOTS_PER_HISTORICAL_ROOT as usize
+            {
                 assert_eq!(
-                    BeaconState::generalized_index(&[
-                        "validators".into(),
-                        index.into(),
-                        "exit_epoch".into(),
-                    ])? as u64,
-                    presets::mainnet::beacon_state::post_electra::validator_exit_epoch(
-                        index as u64
-                    )
+                    HistoricalBatch::generalized_index(&["state_roots".into(), index.into()])?
+                        as u64,
+                    gindices.historical_batch_state_roots(index as u64)
                 );
             }
             Ok(())
         }
     }
 
+    fn u64_leaf(value: u64) -> [u8; 32] {
+        let mut leaf = [0; 32];
+        leaf[..8].copy_from_slice(&value.to_le_bytes());
+        leaf
+    }
+
+    /// The guests apply the generated gindices to the `beacon_state` types, so the fields must be
+    /// read back from a multiproof over such a state
     #[test]
-    fn historical_batch_state_root() -> anyhow::Result<()> {
-        for index in 0_usize..presets::mainnet::beacon_state::SLOTS_PER_HISTORICAL_ROOT as usize {
-            assert_eq!(
-                ethereum_consensus::capella::presets::mainnet::HistoricalBatch::generalized_index(
-                    &["state_roots".into(), index.into(),]
-                )? as u64,
-                presets::mainnet::historical_batch::state_roots(index as u64)
-            );
+    fn mainnet_electra_multiproof() -> anyhow::Result<()> {
+        use beacon_state::electra::{
+            PendingConsolidation, PendingDeposit, PendingPartialWithdrawal,
+        };
+        use beacon_state::mainnet::ElectraBeaconState;
+        use ethereum_consensus::phase0::Validator;
+        use ssz_multiproofs::MultiproofBuilder;
+
+        let mut state = ElectraBeaconState {
+            slot: 11649024,
+            ..Default::default()
+        };
+        for i in 0..6_u64 {
+            state.validators.push(Validator {
+                withdrawal_credentials: [i as u8 + 1; 32].as_slice().try_into().unwrap(),
+                effective_balance: 32_000_000_000,
+                slashed: i % 2 == 0,
+                activation_eligibility_epoch: 10 + i,
+                activation_epoch: 20 + i,
+                exit_epoch: 1000 + i,
+                withdrawable_epoch: 2000 + i,
+                ..Default::default()
+            });
+            state.balances.push(32_000_000_000 + i);
+        }
+        state.state_roots[5] = [5; 32].into();
+        state.finalized_checkpoint.epoch = 364_000;
+        state.latest_execution_payload_header.block_number = 22_000_000;
+        state.latest_execution_payload_header.timestamp = 1_745_000_000;
+        state.latest_execution_payload_header.block_hash = [9; 32].as_slice().try_into().unwrap();
+        state.pending_deposits.push(PendingDeposit {
+            withdrawal_credentials: [7; 32].as_slice().try_into().unwrap(),
+            amount: 1_000_000_000,
+            slot: 42,
+            ..Default::default()
+        });
+        state
+            .pending_partial_withdrawals
+            .push(PendingPartialWithdrawal {
+                validator_index: 3,
+                amount: 5_000_000_000,
+                withdrawable_epoch: 365_000,
+            });
+        state.pending_consolidations.push(PendingConsolidation {
+            source_index: 1,
+            target_index: 4,
+        });
+
+        let gindices =
+            BeaconStateGindices::new::<Mainnet>(Layout::PostElectra, MAINNET_CAPELLA_FORK_SLOT);
+        // balances are packed four to a leaf
+        let mut balances = [0; 32];
+        for (i, balance) in state.balances.iter().skip(4).enumerate() {
+            balances[i * 8..(i + 1) * 8].copy_from_slice(&balance.to_le_bytes());
+        }
+        let expected = [
+            (gindices.slot(), u64_leaf(11649024)),
+            (gindices.validator_count(), u64_leaf(6)),
+            (gindices.state_roots(5), [5; 32]),
+            (gindices.validator_balance(5), balances),
+            (gindices.validator_withdrawal_credentials(4), [5; 32]),
+            (
+                gindices.validator_effective_balance(4),
+                u64_leaf(32_000_000_000),
+            ),
+            (gindices.validator_slashed(4), u64_leaf(1)),
+            (gindices.validator_slashed(5), u64_leaf(0)),
+            (
+                gindices.validator_activation_eligibility_epoch(4),
+                u64_leaf(14),
+            ),
+            (gindices.validator_activation_epoch(4), u64_leaf(24)),
+            (gindices.validator_exit_epoch(4), u64_leaf(1004)),
+            (gindices.validator_withdrawable_epoch(4), u64_leaf(2004)),
+            (gindices.finalized_checkpoint_epoch(), u64_leaf(364_000)),
+            (
+                gindices.latest_execution_payload_header_block_number(),
+                u64_leaf(22_000_000),
+            ),
+            (
+                gindices.latest_execution_payload_header_timestamp(),
+                u64_leaf(1_745_000_000),
+            ),
+            (
+                gindices.latest_execution_payload_header_block_hash(),
+                [9; 32],
+            ),
+            (gindices.pending_deposits_count(), u64_leaf(1)),
+            (gindices.pending_deposit_withdrawal_credentials(0), [7; 32]),
+            (gindices.pending_deposit_amount(0), u64_leaf(1_000_000_000)),
+            (gindices.pending_deposit_slot(0), u64_leaf(42)),
+            (gindices.pending_partial_withdrawals_count(), u64_leaf(1)),
+            (
+                gindices.pending_partial_withdrawal_validator_index(0),
+                u64_leaf(3),
+            ),
+            (
+                gindices.pending_partial_withdrawal_amount(0),
+                u64_leaf(5_000_000_000),
+            ),
+            (
+                gindices.pending_partial_withdrawal_withdrawable_epoch(0),
+                u64_leaf(365_000),
+            ),
+            (gindices.pending_consolidations_count(), u64_leaf(1)),
+            (gindices.pending_consolidation_source_index(0), u64_leaf(1)),
+            (gindices.pending_consolidation_target_index(0), u64_leaf(4)),
+        ];
+
+        let multiproof = MultiproofBuilder::new()
+            .with_gindices(
+                expected
+                    .iter()
+                    .map(|(gindex, _)| (*gindex).try_into().unwrap()),
+            )
+            .build(&state)
+            .expect("Failed to build multiproof");
+        multiproof
+            .verify(&state.hash_tree_root()?)
+            .expect("Failed to verify multiproof");
+        for (gindex, leaf) in expected {
+            let value: &[u8; 32] = &multiproof.get(gindex).expect("Missing gindex");
+            assert_eq!(*value, leaf, "gindex {gindex}");
         }
         Ok(())
     }
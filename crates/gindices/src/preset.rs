@@ -0,0 +1,398 @@
+use crate::presets::{mainnet, minimal};
+
+/// Position of the fields in the BeaconState container. A fork only needs a new layout when
+/// it adds fields or otherwise moves the ones the oracle reads.
+///
+/// [Layout::PreElectra] follows the Deneb state. Capella states share it except for the fields
+/// of the latest execution payload header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    PreElectra,
+    PostElectra,
+    PostFulu,
+}
+
+impl Layout {
+    /// Whether the state has the pending deposit, partial withdrawal and consolidation queues
+    pub fn has_pending_queues(self) -> bool {
+        !matches!(self, Layout::PreElectra)
+    }
+}
+
+/// Gindices of the pending queues added in Electra
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingQueuesTable {
+    pub pending_deposits_count: u64,
+    pub pending_deposit_pubkey_base: u64,
+    pub pending_deposit_withdrawal_credentials_base: u64,
+    pub pending_deposit_amount_base: u64,
+    pub pending_deposit_signature_base: u64,
+    pub pending_deposit_slot_base: u64,
+    pub pending_partial_withdrawals_count: u64,
+    pub pending_partial_withdrawal_validator_index_base: u64,
+    pub pending_partial_withdrawal_amount_base: u64,
+    pub pending_partial_withdrawal_withdrawable_epoch_base: u64,
+    pub pending_consolidations_count: u64,
+    pub pending_consolidation_source_index_base: u64,
+    pub pending_consolidation_target_index_base: u64,
+}
+
+/// Generated gindices of a BeaconState for one preset and layout.
+/// Lists and vectors are given by the gindex of their first element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BeaconStateTable {
+    pub slot: u64,
+    pub validator_count: u64,
+    pub state_roots_base: u64,
+    pub historical_summaries_base: u64,
+    pub validator_balance_base: u64,
+    pub validator_pubkey_base: u64,
+    pub validator_withdrawal_credentials_base: u64,
+    pub validator_effective_balance_base: u64,
+    pub validator_slashed_base: u64,
+    pub validator_activation_eligibility_epoch_base: u64,
+    pub validator_activation_epoch_base: u64,
+    pub validator_exit_epoch_base: u64,
+    pub validator_withdrawable_epoch_base: u64,
+    pub finalized_checkpoint_epoch: u64,
+    pub finalized_checkpoint_root: u64,
+    /// Root of the header, the fields below are leaves of it
+    pub latest_execution_payload_header: u64,
+    pub latest_execution_payload_header_state_root: u64,
+    pub latest_execution_payload_header_block_number: u64,
+    pub latest_execution_payload_header_timestamp: u64,
+    pub latest_execution_payload_header_block_hash: u64,
+    /// Only present from Electra onwards
+    pub pending_queues: Option<PendingQueuesTable>,
+    /// Only present from Fulu onwards
+    pub proposer_lookahead_base: Option<u64>,
+}
+
+/// A consensus preset. The preset fixes the list and vector lengths of the BeaconState and therefore the depth
+/// of every element below them.
+pub trait Preset {
+    const SLOTS_PER_HISTORICAL_ROOT: u64;
+
+    fn beacon_state(layout: Layout) -> BeaconStateTable;
+
+    /// Gindex of the first entry of the state_roots vector of a HistoricalBatch
+    fn historical_batch_state_roots_base() -> u64;
+}
+
+/// The preset of mainnet and the public testnets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mainnet;
+
+/// The preset of local devnets, e.g. kurtosis with `preset: minimal`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Minimal;
+
+macro_rules! beacon_state_table {
+    ($gen:ident) => {
+        BeaconStateTable {
+            slot: crate::$gen::slot(),
+            validator_count: crate::$gen::validator_count(),
+            state_roots_base: crate::$gen::state_roots_base(),
+            historical_summaries_base: crate::$gen::historical_summaries_base(),
+            validator_balance_base: crate::$gen::validator_balance_base(),
+            validator_pubkey_base: crate::$gen::validator_pubkey_base(),
+            validator_withdrawal_credentials_base:
+                crate::$gen::validator_withdrawal_credentials_base(),
+            validator_effective_balance_base: crate::$gen::validator_effective_balance_base(),
+            validator_slashed_base: crate::$gen::validator_slashed_base(),
+            validator_activation_eligibility_epoch_base:
+                crate::$gen::validator_activation_eligibility_epoch_base(),
+            validator_activation_epoch_base: crate::$gen::validator_activation_epoch_base(),
+            validator_exit_epoch_base: crate::$gen::validator_exit_epoch_base(),
+            validator_withdrawable_epoch_base: crate::$gen::validator_withdrawable_epoch_base(),
+            finalized_checkpoint_epoch: crate::$gen::finalized_checkpoint_epoch(),
+            finalized_checkpoint_root: crate::$gen::finalized_checkpoint_root(),
+            latest_execution_payload_header: crate::$gen::latest_execution_payload_header(),
+            latest_execution_payload_header_state_root:
+                crate::$gen::latest_execution_payload_header_state_root(),
+            latest_execution_payload_header_block_number:
+                crate::$gen::latest_execution_payload_header_block_number(),
+            latest_execution_payload_header_timestamp:
+                crate::$gen::latest_execution_payload_header_timestamp(),
+            latest_execution_payload_header_block_hash:
+                crate::$gen::latest_execution_payload_header_block_hash(),
+            pending_queues: None,
+            proposer_lookahead_base: None,
+        }
+    };
+}
+
+macro_rules! pending_queues_table {
+    ($gen:ident) => {
+        PendingQueuesTable {
+            pending_deposits_count: crate::$gen::pending_deposits_count(),
+            pending_deposit_pubkey_base: crate::$gen::pending_deposit_pubkey_base(),
+            pending_deposit_withdrawal_credentials_base:
+                crate::$gen::pending_deposit_withdrawal_credentials_base(),
+            pending_deposit_amount_base: crate::$gen::pending_deposit_amount_base(),
+            pending_deposit_signature_base: crate::$gen::pending_deposit_signature_base(),
+            pending_deposit_slot_base: crate::$gen::pending_deposit_slot_base(),
+            pending_partial_withdrawals_count: crate::$gen::pending_partial_withdrawals_count(),
+            pending_partial_withdrawal_validator_index_base:
+                crate::$gen::pending_partial_withdrawal_validator_index_base(),
+            pending_partial_withdrawal_amount_base:
+                crate::$gen::pending_partial_withdrawal_amount_base(),
+            pending_partial_withdrawal_withdrawable_epoch_base:
+                crate::$gen::pending_partial_withdrawal_withdrawable_epoch_base(),
+            pending_consolidations_count: crate::$gen::pending_consolidations_count(),
+            pending_consolidation_source_index_base:
+                crate::$gen::pending_consolidation_source_index_base(),
+            pending_consolidation_target_index_base:
+                crate::$gen::pending_consolidation_target_index_base(),
+        }
+    };
+}
+
+macro_rules! impl_preset {
+    ($preset:ident, $slots_per_historical_root:expr, $historical_batch:ident, $pre_electra:ident, $post_electra:ident, $post_fulu:ident) => {
+        impl Preset for $preset {
+            const SLOTS_PER_HISTORICAL_ROOT: u64 = $slots_per_historical_root;
+
+            fn beacon_state(layout: Layout) -> BeaconStateTable {
+                match layout {
+                    Layout::PreElectra => beacon_state_table!($pre_electra),
+                    Layout::PostElectra => BeaconStateTable {
+                        pending_queues: Some(pending_queues_table!($post_electra)),
+                        ..beacon_state_table!($post_electra)
+                    },
+                    Layout::PostFulu => BeaconStateTable {
+                        pending_queues: Some(pending_queues_table!($post_fulu)),
+                        proposer_lookahead_base: Some(crate::$post_fulu::proposer_lookahead_base()),
+                        ..beacon_state_table!($post_fulu)
+                    },
+                }
+            }
+
+            fn historical_batch_state_roots_base() -> u64 {
+                crate::$historical_batch::state_roots_base()
+            }
+        }
+    };
+}
+
+impl_preset!(
+    Mainnet,
+    mainnet::beacon_state::SLOTS_PER_HISTORICAL_ROOT,
+    gen_historical_batch,
+    gen_pre_electra,
+    gen_post_electra,
+    gen_post_fulu
+);
+
+impl_preset!(
+    Minimal,
+    minimal::beacon_state::SLOTS_PER_HISTORICAL_ROOT,
+    gen_minimal_historical_batch,
+    gen_minimal_pre_electra,
+    gen_minimal_post_electra,
+    gen_minimal_post_fulu
+);
+
+/// Gindices into a BeaconState selected at runtime from the preset, the layout of the state's fork
+/// and the fork slots of the network
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BeaconStateGindices {
+    table: BeaconStateTable,
+    slots_per_historical_root: u64,
+    historical_batch_state_roots_base: u64,
+    capella_fork_slot: u64,
+}
+
+impl BeaconStateGindices {
+    pub fn new<P: Preset>(layout: Layout, capella_fork_slot: u64) -> Self {
+        Self {
+            table: P::beacon_state(layout),
+            slots_per_historical_root: P::SLOTS_PER_HISTORICAL_ROOT,
+            historical_batch_state_roots_base: P::historical_batch_state_roots_base(),
+            capella_fork_slot,
+        }
+    }
+
+    pub fn table(&self) -> &BeaconStateTable {
+        &self.table
+    }
+
+    pub fn slots_per_historical_root(&self) -> u64 {
+        self.slots_per_historical_root
+    }
+
+    /// Whether the state has the pending deposit, partial withdrawal and consolidation queues
+    pub fn has_pending_queues(&self) -> bool {
+        self.table.pending_queues.is_some()
+    }
+
+    fn pending_queues(&self) -> &PendingQueuesTable {
+        self.table
+            .pending_queues
+            .as_ref()
+            .expect("The pending queues are not part of the BeaconState before Electra")
+    }
+
+    pub fn slot(&self) -> u64 {
+        self.table.slot
+    }
+
+    pub fn validator_count(&self) -> u64 {
+        self.table.validator_count
+    }
+
+    pub fn state_roots(&self, slot: u64) -> u64 {
+        // note this only holds if slot < state.slot <= slot + SLOTS_PER_HISTORICAL_ROOT
+        // otherwise the state_root is not available in the state_roots list
+        let index = slot % self.slots_per_historical_root;
+        self.table.state_roots_base + index
+    }
+
+    pub fn historical_summaries(&self, slot: u64) -> u64 {
+        assert!(
+            slot >= self.capella_fork_slot,
+            "Historical summaries are only available from Capella fork onwards"
+        );
+        let index = (slot - self.capella_fork_slot) / self.slots_per_historical_root;
+        self.table.historical_summaries_base + index
+    }
+
+    /// Gindex of a state root in the HistoricalBatch that a historical summary commits to
+    pub fn historical_batch_state_roots(&self, slot: u64) -> u64 {
+        let index = slot % self.slots_per_historical_root;
+        self.historical_batch_state_roots_base + index
+    }
+
+    pub fn validator_balance(&self, validator_index: u64) -> u64 {
+        self.table.validator_balance_base + (validator_index / 4)
+    }
+
+    pub fn validator_pubkey(&self, validator_index: u64) -> u64 {
+        self.table.validator_pubkey_base + validator_index * 8
+    }
+
+    pub fn validator_withdrawal_credentials(&self, validator_index: u64) -> u64 {
+        self.table.validator_withdrawal_credentials_base + validator_index * 8
+    }
+
+    pub fn validator_effective_balance(&self, validator_index: u64) -> u64 {
+        self.table.validator_effective_balance_base + validator_index * 8
+    }
+
+    pub fn validator_activation_eligibility_epoch(&self, validator_index: u64) -> u64 {
+        self.table.validator_activation_eligibility_epoch_base + validator_index * 8
+    }
+
+    pub fn validator_activation_epoch(&self, validator_index: u64) -> u64 {
+        self.table.validator_activation_epoch_base + validator_index * 8
+    }
+
+    pub fn validator_exit_epoch(&self, validator_index: u64) -> u64 {
+        self.table.validator_exit_epoch_base + validator_index * 8
+    }
+
+    pub fn validator_slashed(&self, validator_index: u64) -> u64 {
+        self.table.validator_slashed_base + validator_index * 8
+    }
+
+    pub fn validator_withdrawable_epoch(&self, validator_index: u64) -> u64 {
+        self.table.validator_withdrawable_epoch_base + validator_index * 8
+    }
+
+    pub fn finalized_checkpoint_epoch(&self) -> u64 {
+        self.table.finalized_checkpoint_epoch
+    }
+
+    pub fn finalized_checkpoint_root(&self) -> u64 {
+        self.table.finalized_checkpoint_root
+    }
+
+    pub fn latest_execution_payload_header(&self) -> u64 {
+        self.table.latest_execution_payload_header
+    }
+
+    pub fn latest_execution_payload_header_state_root(&self) -> u64 {
+        self.table.latest_execution_payload_header_state_root
+    }
+
+    pub fn latest_execution_payload_header_block_number(&self) -> u64 {
+        self.table.latest_execution_payload_header_block_number
+    }
+
+    pub fn latest_execution_payload_header_timestamp(&self) -> u64 {
+        self.table.latest_execution_payload_header_timestamp
+    }
+
+    pub fn latest_execution_payload_header_block_hash(&self) -> u64 {
+        self.table.latest_execution_payload_header_block_hash
+    }
+
+    pub fn pending_deposits_count(&self) -> u64 {
+        self.pending_queues().pending_deposits_count
+    }
+
+    pub fn pending_deposit_pubkey(&self, deposit_index: u64) -> u64 {
+        self.pending_queues().pending_deposit_pubkey_base + deposit_index * 8
+    }
+
+    pub fn pending_deposit_withdrawal_credentials(&self, deposit_index: u64) -> u64 {
+        self.pending_queues()
+            .pending_deposit_withdrawal_credentials_base
+            + deposit_index * 8
+    }
+
+    pub fn pending_deposit_amount(&self, deposit_index: u64) -> u64 {
+        self.pending_queues().pending_deposit_amount_base + deposit_index * 8
+    }
+
+    pub fn pending_deposit_signature(&self, deposit_index: u64) -> u64 {
+        self.pending_queues().pending_deposit_signature_base + deposit_index * 8
+    }
+
+    pub fn pending_deposit_slot(&self, deposit_index: u64) -> u64 {
+        self.pending_queues().pending_deposit_slot_base + deposit_index * 8
+    }
+
+    pub fn pending_partial_withdrawals_count(&self) -> u64 {
+        self.pending_queues().pending_partial_withdrawals_count
+    }
+
+    pub fn pending_partial_withdrawal_validator_index(&self, withdrawal_index: u64) -> u64 {
+        self.pending_queues()
+            .pending_partial_withdrawal_validator_index_base
+            + withdrawal_index * 4
+    }
+
+    pub fn pending_partial_withdrawal_amount(&self, withdrawal_index: u64) -> u64 {
+        self.pending_queues().pending_partial_withdrawal_amount_base + withdrawal_index * 4
+    }
+
+    pub fn pending_partial_withdrawal_withdrawable_epoch(&self, withdrawal_index: u64) -> u64 {
+        self.pending_queues()
+            .pending_partial_withdrawal_withdrawable_epoch_base
+            + withdrawal_index * 4
+    }
+
+    pub fn pending_consolidations_count(&self) -> u64 {
+        self.pending_queues().pending_consolidations_count
+    }
+
+    pub fn pending_consolidation_source_index(&self, consolidation_index: u64) -> u64 {
+        self.pending_queues()
+            .pending_consolidation_source_index_base
+            + consolidation_index * 2
+    }
+
+    pub fn pending_consolidation_target_index(&self, consolidation_index: u64) -> u64 {
+        self.pending_queues()
+            .pending_consolidation_target_index_base
+            + consolidation_index * 2
+    }
+
+    pub fn proposer_lookahead(&self, index: u64) -> u64 {
+        self.table
+            .proposer_lookahead_base
+            .expect("The proposer lookahead is not part of the BeaconState before Fulu")
+            + (index / 4)
+    }
+}
@@ -27,9 +27,37 @@ pub enum Error {
     #[error("The fork version is not currently supported")]
     UnsupportedFork,
 
+    #[error(
+        "A {fork:?} beacon state at slot {slot} does not match the fork schedule of the network"
+    )]
+    ForkMismatch { slot: u64, fork: crate::Fork },
+
+    #[error("The network uses the {expected:?} preset but the beacon state is a {actual:?} state")]
+    PresetMismatch {
+        expected: crate::ConsensusPreset,
+        actual: crate::ConsensusPreset,
+    },
+
     #[error("Historical batch not provided but it is required for proving states are linked over the number of slots they span")]
     MissingHistoricalBatch,
 
+    #[error("The beacon state predates the merge and has no execution payload")]
+    NoExecutionPayload,
+
+    #[error(
+        "The Steel input is for execution block {actual} but the beacon block contains {expected}"
+    )]
+    ExecutionBlockMismatch {
+        expected: alloy_primitives::B256,
+        actual: alloy_primitives::B256,
+    },
+
+    #[error("At least one chunk is required to build a merge")]
+    NoChunks,
+
+    #[error("Journal is not a membership chunk journal")]
+    NotAChunkJournal,
+
     #[error("Internal serde failed: {0}")]
     Risc0Serde(#[from] risc0_zkvm::serde::Error),
 }
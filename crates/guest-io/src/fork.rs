@@ -0,0 +1,59 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use gindices::Layout;
+
+/// Consensus fork of the beacon state that a proof is built from.
+/// Determines which generalized indices the guests use to read the state.
+/// Committed to the balance and exits journal as its discriminant, e.g. `Electra as u8 == 5`.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[repr(u8)]
+pub enum Fork {
+    Phase0,
+    Altair,
+    Bellatrix,
+    Capella,
+    Deneb,
+    Electra,
+    Fulu,
+}
+
+impl Fork {
+    pub fn beacon_state_layout(self) -> Layout {
+        match self {
+            Fork::Phase0 | Fork::Altair | Fork::Bellatrix | Fork::Capella | Fork::Deneb => {
+                Layout::PreElectra
+            }
+            Fork::Electra => Layout::PostElectra,
+            Fork::Fulu => Layout::PostFulu,
+        }
+    }
+}
+
+#[cfg(feature = "builder")]
+impl From<beacon_state::Version> for Fork {
+    fn from(version: beacon_state::Version) -> Self {
+        match version {
+            beacon_state::Version::Phase0 => Fork::Phase0,
+            beacon_state::Version::Altair => Fork::Altair,
+            beacon_state::Version::Bellatrix => Fork::Bellatrix,
+            beacon_state::Version::Capella => Fork::Capella,
+            beacon_state::Version::Deneb => Fork::Deneb,
+            beacon_state::Version::Electra => Fork::Electra,
+            beacon_state::Version::Fulu => Fork::Fulu,
+        }
+    }
+}
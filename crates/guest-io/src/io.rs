@@ -12,7 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::{CredentialSet, Fork};
 use alloy_primitives::B256;
 use alloy_sol_types::sol;
 use bitvec::prelude::*;
@@ -21,13 +22,10 @@ use risc0_zkvm::{sha::Digest, Receipt};
 use ssz_multiproofs::Multiproof;
 #[cfg(feature = "builder")]
 use {
-    crate::error::Error,
-    beacon_state::mainnet::BeaconState,
-    ethereum_consensus::phase0::{presets::mainnet::HistoricalBatch, BeaconBlockHeader},
-    gindices::presets::mainnet::{
-        beacon_block as beacon_block_gindices, beacon_state::post_electra as beacon_state_gindices,
-        beacon_state::SLOTS_PER_HISTORICAL_ROOT, historical_batch as historical_batch_gindices,
-    },
+    crate::{ConsensusPreset, NetworkConfig},
+    beacon_state::PresetBeaconState,
+    ethereum_consensus::phase0::BeaconBlockHeader,
+    gindices::presets::mainnet::beacon_block as beacon_block_gindices,
     ssz_multiproofs::MultiproofBuilder,
     ssz_rs::prelude::*,
 };
@@ -39,7 +37,7 @@ pub struct InputWithReceipt<T> {
 }
 
 pub mod validator_membership {
-    use risc0_zkvm::serde::to_vec;
+    use risc0_zkvm::serde::{from_slice, to_vec};
 
     use super::*;
 
@@ -49,18 +47,24 @@ pub mod validator_membership {
         /// This MUST be written to the journal and checked by the verifier! See https://github.com/risc0/risc0-ethereum/blob/main/contracts/src/RiscZeroSetVerifier.sol#L114
         pub self_program_id: Digest,
 
+        /// Withdrawal credentials that define membership. Its hash is written to the journal
+        pub withdrawal_credentials: CredentialSet,
+
         /// The state root of the state used in the current proof
         pub state_root: B256,
 
+        /// Fork of the state used in the current proof. Selects the gindices of the multiproof
+        pub fork: Fork,
+
         /// the top validator index the membership proof will be extended to
         pub max_validator_index: u64,
 
         /// If this the first proof in the sequence, or a continuation that consumes an existing proof
         pub proof_type: ProofType,
 
-        /// Merkle SSZ proof rooted in the beacon state
+        /// Merkle SSZ proof rooted in the beacon state. Not required for merging chunks
         #[serde(borrow)]
-        pub multiproof: Multiproof<'a>,
+        pub multiproof: Option<Multiproof<'a>>,
 
         /// Merkle SSZ proof rooted in an intermediate beacon state
         pub hist_summary_multiproof: Option<Multiproof<'a>>,
@@ -68,104 +72,250 @@ pub mod validator_membership {
 
     #[cfg(feature = "builder")]
     impl<'a> Input<'a> {
-        #[tracing::instrument(skip(beacon_state, max_validator_index, self_program_id))]
-        pub fn build_initial<D: Into<Digest>>(
-            beacon_state: BeaconState,
+        #[tracing::instrument(skip(
+            network,
+            withdrawal_credentials,
+            beacon_state,
+            max_validator_index,
+            self_program_id
+        ))]
+        pub fn build_initial<S: ProvableBeaconState, D: Into<Digest>>(
+            network: &NetworkConfig,
+            withdrawal_credentials: CredentialSet,
+            beacon_state: S,
             max_validator_index: u64,
             self_program_id: D,
         ) -> Result<Self> {
             let state_root = beacon_state.hash_tree_root()?;
-
-            let proof_builder =
-                MultiproofBuilder::new().with_gindices((0..=max_validator_index).map(|i| {
-                    beacon_state_gindices::validator_withdrawal_credentials(i)
+            let fork = scheduled_fork(network, &beacon_state)?;
+            let beacon_state_gindices = network.beacon_state_gindices(fork);
+
+            let proof_builder = MultiproofBuilder::new()
+                .with_gindex(beacon_state_gindices.slot().try_into()?)
+                .with_gindices((0..=max_validator_index).map(|i| {
+                    beacon_state_gindices
+                        .validator_withdrawal_credentials(i)
                         .try_into()
                         .unwrap()
                 }));
 
-            let multiproof = build_with_versioned_state(proof_builder, &beacon_state)?;
+            let multiproof = beacon_state.build_multiproof(proof_builder)?;
 
             Ok(Self {
                 self_program_id: self_program_id.into(),
+                withdrawal_credentials,
                 state_root,
+                fork,
                 max_validator_index,
                 proof_type: ProofType::Initial,
-                multiproof,
+                multiproof: Some(multiproof),
                 hist_summary_multiproof: None,
             })
         }
 
         #[tracing::instrument(skip(
+            network,
+            withdrawal_credentials,
             prior_beacon_state,
             prior_max_validator_index,
             beacon_state,
             max_validator_index,
             self_program_id
         ))]
-        pub fn build_continuation<D: Into<Digest>>(
-            withdrawal_credentials: B256,
-            prior_beacon_state: &BeaconState,
+        pub fn build_continuation<S: ProvableBeaconState, D: Into<Digest>>(
+            network: &NetworkConfig,
+            withdrawal_credentials: CredentialSet,
+            prior_beacon_state: &S,
             prior_max_validator_index: u64,
-            beacon_state: &BeaconState,
+            beacon_state: &S,
             max_validator_index: u64,
-            historical_batch: Option<HistoricalBatch>,
+            historical_batch: Option<S::HistoricalBatch>,
             self_program_id: D,
         ) -> Result<Self> {
             let state_root = beacon_state.hash_tree_root()?;
+            let fork = scheduled_fork(network, beacon_state)?;
+            let beacon_state_gindices = network.beacon_state_gindices(fork);
             let slot = beacon_state.slot();
             let prior_slot = prior_beacon_state.slot();
 
-            let mut proof_builder = MultiproofBuilder::new().with_gindices(
-                (prior_max_validator_index + 1..=max_validator_index).map(|i| {
-                    beacon_state_gindices::validator_withdrawal_credentials(i)
-                        .try_into()
-                        .unwrap()
-                }),
-            );
+            let mut proof_builder = MultiproofBuilder::new()
+                .with_gindex(beacon_state_gindices.slot().try_into()?)
+                .with_gindices(
+                    (prior_max_validator_index + 1..=max_validator_index).map(|i| {
+                        beacon_state_gindices
+                            .validator_withdrawal_credentials(i)
+                            .try_into()
+                            .unwrap()
+                    }),
+                );
 
             let prior_membership = prior_beacon_state
                 .validators()
                 .iter()
                 .take((prior_max_validator_index + 1) as usize)
-                .map(|v| v.withdrawal_credentials.as_slice() == withdrawal_credentials.as_slice())
+                .map(|v| {
+                    withdrawal_credentials.matches(
+                        v.withdrawal_credentials.as_slice(),
+                        &network.withdrawal_credential_prefixes,
+                    )
+                })
                 .collect::<BitVec<u32, Lsb0>>();
 
             let (cont_type, hist_summary_multiproof) = if slot == prior_slot {
                 (ContinuationType::SameSlot, None)
-            } else if slot <= prior_slot + SLOTS_PER_HISTORICAL_ROOT {
+            } else if slot <= prior_slot + beacon_state_gindices.slots_per_historical_root() {
                 proof_builder = proof_builder
-                    .with_gindex(beacon_state_gindices::state_roots(prior_slot).try_into()?);
+                    .with_gindex(beacon_state_gindices.state_roots(prior_slot).try_into()?);
                 (ContinuationType::ShortRange, None)
             } else if let Some(historical_batch) = historical_batch {
                 proof_builder = proof_builder.with_gindex(
-                    beacon_state_gindices::historical_summaries(prior_slot).try_into()?,
+                    beacon_state_gindices
+                        .historical_summaries(prior_slot)
+                        .try_into()?,
                 );
                 let hist_summary_multiproof = MultiproofBuilder::new()
-                    .with_gindex(historical_batch_gindices::state_roots(prior_slot).try_into()?)
+                    .with_gindex(
+                        beacon_state_gindices
+                            .historical_batch_state_roots(prior_slot)
+                            .try_into()?,
+                    )
                     .build(&historical_batch)?;
                 (ContinuationType::LongRange, Some(hist_summary_multiproof))
             } else {
                 return Err(Error::MissingHistoricalBatch);
             };
 
-            let multiproof = build_with_versioned_state(proof_builder, beacon_state)?;
+            let multiproof = beacon_state.build_multiproof(proof_builder)?;
 
             Ok(Self {
                 self_program_id: self_program_id.into(),
+                withdrawal_credentials,
                 state_root,
+                fork,
                 max_validator_index,
                 proof_type: ProofType::Continuation {
                     prior_state_root: prior_beacon_state.hash_tree_root()?,
                     prior_slot,
+                    prior_fork: Fork::from(prior_beacon_state.version()),
                     prior_max_validator_index,
                     prior_membership,
                     cont_type,
                 },
-                multiproof,
+                multiproof: Some(multiproof),
                 hist_summary_multiproof,
             })
         }
 
+        /// Builds a proof of membership for only the validators start_validator_index..=max_validator_index.
+        /// See [ProofType::Chunk]
+        #[tracing::instrument(skip(
+            network,
+            withdrawal_credentials,
+            beacon_state,
+            self_program_id
+        ))]
+        pub fn build_chunk<S: ProvableBeaconState, D: Into<Digest>>(
+            network: &NetworkConfig,
+            withdrawal_credentials: CredentialSet,
+            beacon_state: &S,
+            start_validator_index: u64,
+            max_validator_index: u64,
+            self_program_id: D,
+        ) -> Result<Self> {
+            let mut chunks = Self::build_chunks(
+                network,
+                withdrawal_credentials,
+                beacon_state,
+                &[(start_validator_index, max_validator_index)],
+                self_program_id,
+            )?;
+            Ok(chunks.remove(0))
+        }
+
+        /// Builds the chunk proofs of the given inclusive validator index ranges of one state.
+        /// The state root and the fork are computed once and shared by all chunks.
+        /// See [ProofType::Chunk]
+        #[tracing::instrument(skip(
+            network,
+            withdrawal_credentials,
+            beacon_state,
+            ranges,
+            self_program_id
+        ))]
+        pub fn build_chunks<S: ProvableBeaconState, D: Into<Digest>>(
+            network: &NetworkConfig,
+            withdrawal_credentials: CredentialSet,
+            beacon_state: &S,
+            ranges: &[(u64, u64)],
+            self_program_id: D,
+        ) -> Result<Vec<Self>> {
+            let self_program_id = self_program_id.into();
+            let state_root = beacon_state.hash_tree_root()?;
+            let fork = scheduled_fork(network, beacon_state)?;
+            let beacon_state_gindices = network.beacon_state_gindices(fork);
+            let slot_gindex = beacon_state_gindices.slot().try_into()?;
+
+            ranges
+                .iter()
+                .map(|&(start_validator_index, max_validator_index)| {
+                    let proof_builder = MultiproofBuilder::new()
+                        .with_gindex(slot_gindex)
+                        .with_gindices((start_validator_index..=max_validator_index).map(|i| {
+                            beacon_state_gindices
+                                .validator_withdrawal_credentials(i)
+                                .try_into()
+                                .unwrap()
+                        }));
+
+                    let multiproof = beacon_state.build_multiproof(proof_builder)?;
+
+                    Ok(Self {
+                        self_program_id,
+                        withdrawal_credentials: withdrawal_credentials.clone(),
+                        state_root,
+                        fork,
+                        max_validator_index,
+                        proof_type: ProofType::Chunk {
+                            start_validator_index,
+                        },
+                        multiproof: Some(multiproof),
+                        hist_summary_multiproof: None,
+                    })
+                })
+                .collect()
+        }
+
+        /// Builds an input that combines chunk proofs, given in validator index order, into a regular membership proof.
+        /// See [ProofType::Merge]
+        #[tracing::instrument(skip_all)]
+        pub fn build_merge<D: Into<Digest>>(
+            withdrawal_credentials: CredentialSet,
+            chunks: Vec<(ChunkJournal, Receipt)>,
+            self_program_id: D,
+        ) -> Result<Self> {
+            let (Some((first, _)), Some((last, _))) = (chunks.first(), chunks.last()) else {
+                return Err(Error::NoChunks);
+            };
+            let state_root = first.state_root;
+            let fork = first.fork;
+            let max_validator_index = last.max_validator_index;
+            let (chunks, chunk_receipts) = chunks.into_iter().unzip();
+
+            Ok(Self {
+                self_program_id: self_program_id.into(),
+                withdrawal_credentials,
+                state_root,
+                fork,
+                max_validator_index,
+                proof_type: ProofType::Merge {
+                    chunks,
+                    chunk_receipts,
+                },
+                multiproof: None,
+                hist_summary_multiproof: None,
+            })
+        }
+
         pub fn without_receipt(self) -> InputWithReceipt<Self> {
             InputWithReceipt {
                 input: self,
@@ -187,10 +337,23 @@ pub mod validator_membership {
         Continuation {
             prior_state_root: B256,
             prior_slot: u64,
+            /// Fork of the prior state, committed in the prior journal
+            prior_fork: Fork,
             prior_max_validator_index: u64,
             prior_membership: BitVec<u32, Lsb0>,
             cont_type: ContinuationType,
         },
+        /// Proves membership of only the validators start_validator_index..=max_validator_index and commits a [ChunkJournal].
+        /// Chunks of a single state can be proven in parallel and then combined with [ProofType::Merge].
+        Chunk {
+            start_validator_index: u64,
+        },
+        /// Verifies chunk proofs that together cover validators 0..=max_validator_index in order and
+        /// concatenates their membership into a regular [Journal]. Receipts are given in the same order as the chunks.
+        Merge {
+            chunks: Vec<ChunkJournal>,
+            chunk_receipts: Vec<Receipt>,
+        },
     }
 
     /// Continuations proofs are slightly different depending on how far back the prior proof is.
@@ -217,7 +380,13 @@ pub mod validator_membership {
     #[derive(Debug, serde::Serialize, serde::Deserialize)]
     pub struct Journal {
         pub self_program_id: Digest,
+        /// Withdrawal credential prefixes (e.g. 0x01, 0x02) that were accepted as membership
+        pub withdrawal_credential_prefixes: Vec<u8>,
+        /// Hash of the withdrawal credentials membership was checked against. See [CredentialSet::hash]
+        pub withdrawal_credentials_hash: B256,
         pub state_root: B256,
+        /// Fork of the state, which determines where membership was read from
+        pub fork: Fork,
         pub max_validator_index: u64,
         pub membership: BitVec<u32, Lsb0>,
     }
@@ -227,6 +396,42 @@ pub mod validator_membership {
             Ok(bytemuck::cast_slice(&to_vec(self)?).to_vec())
         }
     }
+
+    /// Domain separator written ahead of a [ChunkJournal] so it can never be mistaken for a [Journal]
+    /// produced by the same program
+    pub const CHUNK_JOURNAL_DOMAIN: [u8; 32] = *b"lido-oracle.membership-chunk.v1\0";
+
+    /// Journal committed by [ProofType::Chunk] proofs
+    #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+    pub struct ChunkJournal {
+        pub self_program_id: Digest,
+        pub withdrawal_credential_prefixes: Vec<u8>,
+        pub withdrawal_credentials_hash: B256,
+        pub state_root: B256,
+        pub fork: Fork,
+        pub start_validator_index: u64,
+        pub max_validator_index: u64,
+        /// Membership of validators start_validator_index..=max_validator_index
+        pub membership: BitVec<u32, Lsb0>,
+    }
+
+    impl ChunkJournal {
+        pub fn to_bytes(&self) -> Result<Vec<u8>> {
+            Ok(bytemuck::cast_slice(&to_vec(&(CHUNK_JOURNAL_DOMAIN, self))?).to_vec())
+        }
+
+        pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+            let words: Vec<u32> = bytes
+                .chunks_exact(4)
+                .map(|w| u32::from_le_bytes(w.try_into().unwrap()))
+                .collect();
+            let (domain, journal): ([u8; 32], Self) = from_slice(&words)?;
+            if domain != CHUNK_JOURNAL_DOMAIN {
+                return Err(Error::NotAChunkJournal);
+            }
+            Ok(journal)
+        }
+    }
 }
 
 pub mod balance_and_exits {
@@ -239,6 +444,9 @@ pub mod balance_and_exits {
         /// Block that the proof is rooted in
         pub block_root: B256,
 
+        /// Fork of the beacon state. Selects the gindices of the state multiproof
+        pub fork: Fork,
+
         /// Bitfield indicating which validators are members of the Lido set
         pub membership: BitVec<u32, Lsb0>,
 
@@ -255,46 +463,123 @@ pub mod balance_and_exits {
 
     #[cfg(feature = "builder")]
     impl Input<'_> {
-        #[tracing::instrument(skip(block_header, beacon_state, evm_input))]
-        pub fn build(
-            withdrawal_credentials: B256,
+        #[tracing::instrument(skip(network, block_header, beacon_state, evm_input))]
+        pub fn build<S: ProvableBeaconState>(
+            network: &NetworkConfig,
             block_header: &BeaconBlockHeader,
-            beacon_state: &BeaconState,
+            beacon_state: &S,
             evm_input: EthEvmInput,
         ) -> Result<Self> {
             let block_root = block_header.hash_tree_root()?;
+            let fork = scheduled_fork(network, beacon_state)?;
+            // Capella states have a smaller execution payload header than the gindices describe
+            if fork < Fork::Deneb {
+                return Err(Error::UnsupportedFork);
+            }
+            let beacon_state_gindices = network.beacon_state_gindices(fork);
+
+            // The guest only accepts the execution block carried by the proven beacon block
+            let execution_block_hash = beacon_state
+                .latest_execution_block_hash()
+                .ok_or(Error::NoExecutionPayload)?;
+            let evm_block_hash = evm_input
+                .clone()
+                .into_env(&network.chain_spec)
+                .header()
+                .seal();
+            if evm_block_hash.as_slice() != execution_block_hash.as_slice() {
+                return Err(Error::ExecutionBlockMismatch {
+                    expected: B256::from_slice(execution_block_hash.as_slice()),
+                    actual: evm_block_hash,
+                });
+            }
 
             let membership = beacon_state
                 .validators()
                 .iter()
-                .map(|v| v.withdrawal_credentials.as_slice() == withdrawal_credentials.as_slice())
+                .map(|v| network.is_member(v.withdrawal_credentials.as_slice()))
                 .collect::<BitVec<u32, Lsb0>>();
 
             tracing::info!("{} Lido validators detected", membership.count_ones());
 
+            // The pending queues only exist from Electra onwards. Earlier states report them as empty
+            let pending_deposits_count = beacon_state
+                .pending_deposits()
+                .map_or(0, |list| list.len() as u64);
+            let pending_partial_withdrawals_count = beacon_state
+                .pending_partial_withdrawals()
+                .map_or(0, |list| list.len() as u64);
+            let pending_consolidations_count = beacon_state
+                .pending_consolidations()
+                .map_or(0, |list| list.len() as u64);
+
             let block_multiproof = MultiproofBuilder::new()
                 .with_gindex(beacon_block_gindices::slot().try_into()?)
                 .with_gindex(beacon_block_gindices::state_root().try_into()?)
                 .build(block_header)?;
 
-            let state_multiproof_builder = MultiproofBuilder::new()
-                .with_gindex(beacon_state_gindices::validator_count().try_into()?)
+            let mut state_multiproof_builder = MultiproofBuilder::new()
+                .with_gindex(beacon_state_gindices.validator_count().try_into()?)
                 .with_gindices(membership.iter_ones().map(|i| {
-                    beacon_state_gindices::validator_balance(i as u64)
+                    beacon_state_gindices
+                        .validator_balance(i as u64)
                         .try_into()
                         .unwrap()
                 }))
-                .with_gindices(membership.iter_ones().map(|i| {
-                    beacon_state_gindices::validator_exit_epoch(i as u64)
-                        .try_into()
-                        .unwrap()
-                }));
+                .with_gindices(membership.iter_ones().flat_map(|i| {
+                    [
+                        beacon_state_gindices.validator_slashed(i as u64),
+                        beacon_state_gindices.validator_exit_epoch(i as u64),
+                        beacon_state_gindices.validator_withdrawable_epoch(i as u64),
+                    ]
+                    .map(|gindex| gindex.try_into().unwrap())
+                }))
+                .with_gindex(
+                    beacon_state_gindices
+                        .latest_execution_payload_header_block_hash()
+                        .try_into()?,
+                );
+            if beacon_state_gindices.has_pending_queues() {
+                state_multiproof_builder = state_multiproof_builder
+                    .with_gindex(beacon_state_gindices.pending_deposits_count().try_into()?)
+                    .with_gindices((0..pending_deposits_count).flat_map(|i| {
+                        [
+                            beacon_state_gindices.pending_deposit_withdrawal_credentials(i),
+                            beacon_state_gindices.pending_deposit_amount(i),
+                        ]
+                        .map(|gindex| gindex.try_into().unwrap())
+                    }))
+                    .with_gindex(
+                        beacon_state_gindices
+                            .pending_partial_withdrawals_count()
+                            .try_into()?,
+                    )
+                    .with_gindices((0..pending_partial_withdrawals_count).flat_map(|i| {
+                        [
+                            beacon_state_gindices.pending_partial_withdrawal_validator_index(i),
+                            beacon_state_gindices.pending_partial_withdrawal_amount(i),
+                        ]
+                        .map(|gindex| gindex.try_into().unwrap())
+                    }))
+                    .with_gindex(
+                        beacon_state_gindices
+                            .pending_consolidations_count()
+                            .try_into()?,
+                    )
+                    .with_gindices((0..pending_consolidations_count).flat_map(|i| {
+                        [
+                            beacon_state_gindices.pending_consolidation_source_index(i),
+                            beacon_state_gindices.pending_consolidation_target_index(i),
+                        ]
+                        .map(|gindex| gindex.try_into().unwrap())
+                    }));
+            }
 
-            let state_multiproof =
-                build_with_versioned_state(state_multiproof_builder, beacon_state)?;
+            let state_multiproof = beacon_state.build_multiproof(state_multiproof_builder)?;
 
             Ok(Self {
                 block_root,
+                fork,
                 membership,
                 block_multiproof,
                 state_multiproof,
@@ -311,28 +596,91 @@ pub mod balance_and_exits {
     }
 
     sol! {
+        /// Proven figures reported alongside the LIP-23 report
+        struct AuxiliaryReport {
+            /// Sum of pending deposits with Lido withdrawal credentials that are not yet applied to balances
+            uint256 pendingDepositsGwei;
+            /// Sum of pending partial withdrawals from Lido validators
+            uint256 pendingPartialWithdrawalsGwei;
+            /// Number of pending consolidations with a Lido validator as the source
+            uint256 pendingConsolidationsFromLido;
+            /// Number of pending consolidations with a Lido validator as the target
+            uint256 pendingConsolidationsToLido;
+            /// Number of Lido validators that have been slashed
+            uint256 slashedValidators;
+            /// Sum of balances of slashed Lido validators that are not yet withdrawable
+            uint256 slashedBalanceGwei;
+        }
+
         struct Journal {
             uint256 clBalanceGwei;
             uint256 withdrawalVaultBalanceWei;
             uint256 totalDepositedValidators;
             uint256 totalExitedValidators;
+            AuxiliaryReport auxiliary;
             bytes32 blockRoot;
+            uint256 slot;
+            bytes32 stateRoot;
+            /// Fork of the beacon state, see [crate::Fork]
+            uint8 fork;
             Commitment commitment;
         }
     }
 }
 
+/// A beacon state of a preset the guests support, which input multiproofs can be built over
 #[cfg(feature = "builder")]
-fn build_with_versioned_state(
-    builder: MultiproofBuilder,
-    beacon_state: &BeaconState,
-) -> Result<Multiproof<'static>> {
-    match beacon_state {
-        BeaconState::Phase0(b) => Ok(builder.build(b)?),
-        BeaconState::Altair(b) => Ok(builder.build(b)?),
-        BeaconState::Bellatrix(b) => Ok(builder.build(b)?),
-        BeaconState::Capella(b) => Ok(builder.build(b)?),
-        BeaconState::Deneb(b) => Ok(builder.build(b)?),
-        BeaconState::Electra(b) => Ok(builder.build(b)?),
+pub trait ProvableBeaconState: PresetBeaconState {
+    const PRESET: ConsensusPreset;
+
+    /// Builds the multiproof over the container of the fork of the state
+    fn build_multiproof(&self, builder: MultiproofBuilder) -> Result<Multiproof<'static>>;
+}
+
+#[cfg(feature = "builder")]
+macro_rules! impl_provable_beacon_state {
+    ($preset:ident, $consensus_preset:expr) => {
+        impl ProvableBeaconState for beacon_state::$preset::BeaconState {
+            const PRESET: ConsensusPreset = $consensus_preset;
+
+            fn build_multiproof(&self, builder: MultiproofBuilder) -> Result<Multiproof<'static>> {
+                match self {
+                    Self::Phase0(b) => Ok(builder.build(b)?),
+                    Self::Altair(b) => Ok(builder.build(b)?),
+                    Self::Bellatrix(b) => Ok(builder.build(b)?),
+                    Self::Capella(b) => Ok(builder.build(b)?),
+                    Self::Deneb(b) => Ok(builder.build(b)?),
+                    Self::Electra(b) => Ok(builder.build(b)?),
+                    Self::Fulu(b) => Ok(builder.build(b)?),
+                }
+            }
+        }
+    };
+}
+
+#[cfg(feature = "builder")]
+impl_provable_beacon_state!(mainnet, ConsensusPreset::Mainnet);
+#[cfg(feature = "builder")]
+impl_provable_beacon_state!(minimal, ConsensusPreset::Minimal);
+
+/// Fork of the beacon state, which must be the fork the network schedules at the slot of the state.
+/// The guests check the same, so a proof can only commit to the fork of the proven state.
+#[cfg(feature = "builder")]
+fn scheduled_fork<S: ProvableBeaconState>(
+    network: &NetworkConfig,
+    beacon_state: &S,
+) -> Result<Fork> {
+    if S::PRESET != network.preset {
+        return Err(Error::PresetMismatch {
+            expected: network.preset,
+            actual: S::PRESET,
+        });
+    }
+    let fork = Fork::from(beacon_state.version());
+    let slot = beacon_state.slot();
+    match network.fork_at_slot(slot) {
+        Some(scheduled) if scheduled == fork => Ok(fork),
+        Some(_) => Err(Error::ForkMismatch { slot, fork }),
+        None => Err(Error::UnsupportedFork),
     }
 }
@@ -13,45 +13,11 @@
 // limitations under the License.
 
 mod error;
+mod fork;
 mod io;
-
-use revm::primitives::hardfork::SpecId;
-use risc0_steel::config::{ChainSpec, ForkCondition};
-use risc0_steel::ethereum::EthChainSpec;
-pub use risc0_steel::ethereum::ETH_SEPOLIA_CHAIN_SPEC;
-use std::collections::BTreeMap;
-use std::sync::LazyLock;
-
-pub mod mainnet {
-    use alloy_primitives::{address, Address};
-
-    pub const WITHDRAWAL_CREDENTIALS: alloy_primitives::B256 = alloy_primitives::B256::new([
-        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xb9, 0xd7, 0x93,
-        0x48, 0x78, 0xb5, 0xfb, 0x96, 0x10, 0xb3, 0xfe, 0x8a, 0x5e, 0x44, 0x1e, 0x8f, 0xad, 0x7e,
-        0x29, 0x3f,
-    ]);
-
-    pub const WITHDRAWAL_VAULT_ADDRESS: Address =
-        address!("b9d7934878b5fb9610b3fe8a5e441e8fad7e293f");
-}
-
-pub mod sepolia {
-    use alloy_primitives::{address, Address};
-
-    pub const WITHDRAWAL_CREDENTIALS: alloy_primitives::B256 = alloy_primitives::B256::new([
-        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xde, 0x73, 0x18,
-        0xaf, 0xa6, 0x7e, 0xad, 0x6d, 0x6b, 0xbc, 0x82, 0x24, 0xdf, 0xce, 0x5e, 0xd6, 0xe4, 0xb8,
-        0x6d, 0x76,
-    ]);
-
-    pub const WITHDRAWAL_VAULT_ADDRESS: Address =
-        address!("De7318Afa67eaD6d6bbC8224dfCe5ed6e4b86d76");
-}
-
-pub static ANVIL_CHAIN_SPEC: LazyLock<EthChainSpec> = LazyLock::new(|| ChainSpec {
-    chain_id: 31337,
-    forks: BTreeMap::from([(SpecId::PRAGUE, ForkCondition::Timestamp(0))]),
-});
+pub mod network;
 
 pub use error::{Error, Result};
+pub use fork::Fork;
 pub use io::*;
+pub use network::{ConsensusPreset, CredentialSet, NetworkConfig};
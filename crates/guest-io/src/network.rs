@@ -0,0 +1,575 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::Fork;
+use alloy_primitives::{address, Address, B256};
+use gindices::{BeaconStateGindices, Mainnet, Minimal};
+use revm::primitives::hardfork::SpecId;
+use risc0_steel::config::{ChainSpec, ForkCondition};
+use risc0_steel::ethereum::{EthChainSpec, ETH_MAINNET_CHAIN_SPEC, ETH_SEPOLIA_CHAIN_SPEC};
+use risc0_zkvm::sha::{Impl as Sha256Impl, Sha256};
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+/// Prefix of withdrawal credentials that commit to an execution layer address
+pub const ETH1_ADDRESS_WITHDRAWAL_PREFIX: u8 = 0x01;
+
+/// Prefix of withdrawal credentials of compounding validators (EIP-7251). These also commit to an execution layer address
+pub const COMPOUNDING_WITHDRAWAL_PREFIX: u8 = 0x02;
+
+/// Consensus preset of a network. Fixes the list and vector lengths of the beacon state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsensusPreset {
+    Mainnet,
+    /// Used by local devnets, e.g. kurtosis with `preset: minimal`
+    Minimal,
+}
+
+impl ConsensusPreset {
+    pub fn slots_per_epoch(self) -> u64 {
+        match self {
+            ConsensusPreset::Mainnet => 32,
+            ConsensusPreset::Minimal => 8,
+        }
+    }
+}
+
+/// Everything about a network that the oracle needs to know to build and verify proofs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkConfig {
+    /// Withdrawal credentials identifying Lido validators. The prefix byte is ignored when matching,
+    /// see [NetworkConfig::withdrawal_credential_prefixes]
+    pub withdrawal_credentials: B256,
+    /// Sorted withdrawal credential prefixes that are accepted as pointing at the withdrawal vault
+    pub withdrawal_credential_prefixes: Vec<u8>,
+    /// Address of the Lido WithdrawalVault contract on the execution layer
+    pub withdrawal_vault_address: Address,
+    /// Steel chain spec of the execution layer
+    pub chain_spec: EthChainSpec,
+    /// Unix timestamp of the beacon chain genesis
+    pub genesis_time: u64,
+    /// Consensus preset, which selects the gindices of the beacon state and how hosts decode it
+    pub preset: ConsensusPreset,
+    /// First slot of the Capella fork. The historical_summaries list starts here
+    pub capella_fork_slot: u64,
+    /// First slot of the Deneb fork
+    pub deneb_fork_slot: u64,
+    /// First slot of the Electra fork, if scheduled
+    pub electra_fork_slot: Option<u64>,
+    /// First slot of the Fulu fork, if scheduled
+    pub fulu_fork_slot: Option<u64>,
+    /// Length of the block_roots and state_roots lists in the beacon state
+    pub slots_per_historical_root: u64,
+    pub seconds_per_slot: u64,
+}
+
+impl NetworkConfig {
+    /// Returns true if the given validator withdrawal credentials belong to a Lido validator
+    pub fn is_member(&self, credentials: &[u8]) -> bool {
+        credentials_match(
+            credentials,
+            &self.withdrawal_credentials,
+            &self.withdrawal_credential_prefixes,
+        )
+    }
+
+    /// The set of withdrawal credentials identifying Lido validators
+    pub fn credential_set(&self) -> CredentialSet {
+        CredentialSet::new([self.withdrawal_credentials])
+    }
+
+    /// Unix timestamp of the start of the given slot
+    pub fn slot_timestamp(&self, slot: u64) -> u64 {
+        self.genesis_time + slot * self.seconds_per_slot
+    }
+
+    /// Fork of the beacon state at the given slot according to the fork schedule.
+    /// Slots before Capella have no fork as the guests cannot read states without
+    /// an execution payload header and historical summaries.
+    pub fn fork_at_slot(&self, slot: u64) -> Option<Fork> {
+        let activated =
+            |fork_slot: Option<u64>| fork_slot.is_some_and(|fork_slot| slot >= fork_slot);
+        if activated(self.fulu_fork_slot) {
+            Some(Fork::Fulu)
+        } else if activated(self.electra_fork_slot) {
+            Some(Fork::Electra)
+        } else if slot >= self.deneb_fork_slot {
+            Some(Fork::Deneb)
+        } else if slot >= self.capella_fork_slot {
+            Some(Fork::Capella)
+        } else {
+            None
+        }
+    }
+
+    /// Gindices into a beacon state of the given fork on this network
+    pub fn beacon_state_gindices(&self, fork: Fork) -> BeaconStateGindices {
+        let layout = fork.beacon_state_layout();
+        match self.preset {
+            ConsensusPreset::Mainnet => {
+                BeaconStateGindices::new::<Mainnet>(layout, self.capella_fork_slot)
+            }
+            ConsensusPreset::Minimal => {
+                BeaconStateGindices::new::<Minimal>(layout, self.capella_fork_slot)
+            }
+        }
+    }
+}
+
+/// Returns true if `credentials` are equal to `expected` ignoring the prefix byte, and the prefix is one of `prefixes`.
+/// This allows a validator switching from 0x01 to 0x02 credentials to remain a member.
+pub fn credentials_match(credentials: &[u8], expected: &B256, prefixes: &[u8]) -> bool {
+    credentials.len() == 32
+        && prefixes.contains(&credentials[0])
+        && credentials[1..] == expected[1..]
+}
+
+/// A sorted and deduplicated list of withdrawal credentials. A validator is a member of the set
+/// if its credentials match any of the entries.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CredentialSet(Vec<B256>);
+
+impl CredentialSet {
+    pub fn new(credentials: impl IntoIterator<Item = B256>) -> Self {
+        let mut credentials: Vec<B256> = credentials.into_iter().collect();
+        credentials.sort_unstable();
+        credentials.dedup();
+        Self(credentials)
+    }
+
+    /// Checks the set is strictly sorted. Sets that are deserialized must be checked before they are hashed
+    /// otherwise the same set could produce different hashes.
+    pub fn is_canonical(&self) -> bool {
+        self.0.windows(2).all(|w| w[0] < w[1])
+    }
+
+    /// SHA-256 of the concatenated credentials. This is what is committed to in the membership journal
+    pub fn hash(&self) -> B256 {
+        let bytes: Vec<u8> = self.0.iter().flat_map(|c| c.0).collect();
+        B256::from_slice(Sha256Impl::hash_bytes(&bytes).as_bytes())
+    }
+
+    /// Returns true if `credentials` match any entry in the set with one of the accepted prefixes
+    pub fn matches(&self, credentials: &[u8], prefixes: &[u8]) -> bool {
+        self.0
+            .iter()
+            .any(|expected| credentials_match(credentials, expected, prefixes))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &B256> {
+        self.0.iter()
+    }
+}
+
+fn lido_withdrawal_credential_prefixes() -> Vec<u8> {
+    vec![
+        ETH1_ADDRESS_WITHDRAWAL_PREFIX,
+        COMPOUNDING_WITHDRAWAL_PREFIX,
+    ]
+}
+
+/// Builds 0x01 withdrawal credentials pointing at the given execution layer address
+pub fn eth1_withdrawal_credentials(address: Address) -> B256 {
+    let mut credentials = B256::ZERO;
+    credentials[0] = ETH1_ADDRESS_WITHDRAWAL_PREFIX;
+    credentials[12..].copy_from_slice(address.as_slice());
+    credentials
+}
+
+pub static MAINNET: LazyLock<NetworkConfig> = LazyLock::new(|| {
+    let withdrawal_vault_address = address!("b9d7934878b5fb9610b3fe8a5e441e8fad7e293f");
+    NetworkConfig {
+        withdrawal_credentials: eth1_withdrawal_credentials(withdrawal_vault_address),
+        withdrawal_credential_prefixes: lido_withdrawal_credential_prefixes(),
+        withdrawal_vault_address,
+        chain_spec: ETH_MAINNET_CHAIN_SPEC.clone(),
+        genesis_time: 1606824023,
+        preset: ConsensusPreset::Mainnet,
+        capella_fork_slot: 6209536,
+        deneb_fork_slot: 8626176,
+        electra_fork_slot: Some(11649024),
+        fulu_fork_slot: Some(13164544),
+        slots_per_historical_root: 8192,
+        seconds_per_slot: 12,
+    }
+});
+
+pub static SEPOLIA: LazyLock<NetworkConfig> = LazyLock::new(|| {
+    let withdrawal_vault_address = address!("De7318Afa67eaD6d6bbC8224dfCe5ed6e4b86d76");
+    NetworkConfig {
+        withdrawal_credentials: eth1_withdrawal_credentials(withdrawal_vault_address),
+        withdrawal_credential_prefixes: lido_withdrawal_credential_prefixes(),
+        withdrawal_vault_address,
+        chain_spec: ETH_SEPOLIA_CHAIN_SPEC.clone(),
+        genesis_time: 1655733600,
+        preset: ConsensusPreset::Mainnet,
+        capella_fork_slot: 1818624,
+        deneb_fork_slot: 4243456,
+        electra_fork_slot: Some(7118848),
+        fulu_fork_slot: Some(8724480),
+        slots_per_historical_root: 8192,
+        seconds_per_slot: 12,
+    }
+});
+
+pub static HOLESKY: LazyLock<NetworkConfig> = LazyLock::new(|| {
+    let withdrawal_vault_address = address!("f0179dec45a37423ead4fad5fcb136197872ead9");
+    NetworkConfig {
+        withdrawal_credentials: eth1_withdrawal_credentials(withdrawal_vault_address),
+        withdrawal_credential_prefixes: lido_withdrawal_credential_prefixes(),
+        withdrawal_vault_address,
+        chain_spec: ChainSpec {
+            chain_id: 17000,
+            forks: BTreeMap::from([
+                (SpecId::MERGE, ForkCondition::Block(0)),
+                (SpecId::SHANGHAI, ForkCondition::Timestamp(1696000704)),
+                (SpecId::CANCUN, ForkCondition::Timestamp(1707305664)),
+                (SpecId::PRAGUE, ForkCondition::Timestamp(1740434112)),
+            ]),
+        },
+        genesis_time: 1695902400,
+        preset: ConsensusPreset::Mainnet,
+        capella_fork_slot: 8192,
+        deneb_fork_slot: 950272,
+        electra_fork_slot: Some(3710976),
+        fulu_fork_slot: Some(5283840),
+        slots_per_historical_root: 8192,
+        seconds_per_slot: 12,
+    }
+});
+
+pub static HOODI: LazyLock<NetworkConfig> = LazyLock::new(|| {
+    let withdrawal_vault_address = address!("4473dcddbf77679a643bdb654dbd86d67f8d32f2");
+    NetworkConfig {
+        withdrawal_credentials: eth1_withdrawal_credentials(withdrawal_vault_address),
+        withdrawal_credential_prefixes: lido_withdrawal_credential_prefixes(),
+        withdrawal_vault_address,
+        chain_spec: ChainSpec {
+            chain_id: 560048,
+            forks: BTreeMap::from([
+                (SpecId::MERGE, ForkCondition::Block(0)),
+                (SpecId::SHANGHAI, ForkCondition::Timestamp(0)),
+                (SpecId::CANCUN, ForkCondition::Timestamp(0)),
+                (SpecId::PRAGUE, ForkCondition::Timestamp(1742999832)),
+            ]),
+        },
+        genesis_time: 1742213400,
+        preset: ConsensusPreset::Mainnet,
+        capella_fork_slot: 0,
+        deneb_fork_slot: 0,
+        electra_fork_slot: Some(65536),
+        fulu_fork_slot: Some(1622016),
+        slots_per_historical_root: 8192,
+        seconds_per_slot: 12,
+    }
+});
+
+/// A local Anvil chain using the mainnet Lido addresses and beacon chain parameters. Only useful for testing.
+pub static ANVIL: LazyLock<NetworkConfig> = LazyLock::new(|| NetworkConfig {
+    chain_spec: ChainSpec {
+        chain_id: 31337,
+        forks: BTreeMap::from([(SpecId::PRAGUE, ForkCondition::Timestamp(0))]),
+    },
+    ..MAINNET.clone()
+});
+
+#[cfg(feature = "loader")]
+pub use loader::*;
+
+#[cfg(feature = "loader")]
+mod loader {
+    use super::*;
+    use gindices::Preset;
+    use std::path::Path;
+
+    /// Errors returned when loading a [NetworkConfig] from a file
+    #[derive(Debug, thiserror::Error)]
+    pub enum LoadError {
+        #[error("Failed to read network config: {0}")]
+        Io(#[from] std::io::Error),
+        #[error("Invalid JSON network config: {0}")]
+        Json(#[from] serde_json::Error),
+        #[error("Invalid TOML network config: {0}")]
+        Toml(#[from] toml::de::Error),
+        #[error("Unsupported network config file extension: {0:?}")]
+        UnsupportedExtension(Option<String>),
+        #[error("Unknown execution layer fork: {0}")]
+        UnknownFork(String),
+    }
+
+    /// On-disk representation of a [NetworkConfig].
+    ///
+    /// Execution layer forks are given as a map of fork name to activation timestamp, e.g.
+    ///
+    /// ```toml
+    /// withdrawal_vault_address = "0x..."
+    /// genesis_time = 1742213400
+    /// preset = "minimal"
+    /// capella_fork_slot = 0
+    /// deneb_fork_slot = 0
+    /// electra_fork_slot = 0
+    ///
+    /// [chain]
+    /// chain_id = 3151908
+    /// forks = { cancun = 0, prague = 0 }
+    /// ```
+    #[derive(Debug, serde::Deserialize)]
+    struct NetworkConfigFile {
+        /// Defaults to the 0x01 credentials of the withdrawal vault if omitted
+        withdrawal_credentials: Option<B256>,
+        /// Defaults to 0x01 and 0x02 if omitted
+        withdrawal_credential_prefixes: Option<Vec<u8>>,
+        withdrawal_vault_address: Address,
+        chain: ChainSpecFile,
+        genesis_time: u64,
+        /// Defaults to the mainnet preset if omitted
+        #[serde(default = "default_preset")]
+        preset: ConsensusPreset,
+        capella_fork_slot: u64,
+        deneb_fork_slot: u64,
+        electra_fork_slot: Option<u64>,
+        fulu_fork_slot: Option<u64>,
+        /// Defaults to the value of the preset if omitted
+        slots_per_historical_root: Option<u64>,
+        #[serde(default = "default_seconds_per_slot")]
+        seconds_per_slot: u64,
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct ChainSpecFile {
+        chain_id: u64,
+        forks: BTreeMap<String, u64>,
+    }
+
+    fn default_preset() -> ConsensusPreset {
+        ConsensusPreset::Mainnet
+    }
+
+    fn preset_slots_per_historical_root(preset: ConsensusPreset) -> u64 {
+        match preset {
+            ConsensusPreset::Mainnet => Mainnet::SLOTS_PER_HISTORICAL_ROOT,
+            ConsensusPreset::Minimal => Minimal::SLOTS_PER_HISTORICAL_ROOT,
+        }
+    }
+
+    fn default_seconds_per_slot() -> u64 {
+        MAINNET.seconds_per_slot
+    }
+
+    fn spec_id(fork: &str) -> Result<SpecId, LoadError> {
+        match fork.to_lowercase().as_str() {
+            "merge" | "paris" => Ok(SpecId::MERGE),
+            "shanghai" => Ok(SpecId::SHANGHAI),
+            "cancun" => Ok(SpecId::CANCUN),
+            "prague" => Ok(SpecId::PRAGUE),
+            _ => Err(LoadError::UnknownFork(fork.to_string())),
+        }
+    }
+
+    impl TryFrom<NetworkConfigFile> for NetworkConfig {
+        type Error = LoadError;
+
+        fn try_from(file: NetworkConfigFile) -> Result<Self, Self::Error> {
+            let forks = file
+                .chain
+                .forks
+                .iter()
+                .map(|(name, timestamp)| Ok((spec_id(name)?, ForkCondition::Timestamp(*timestamp))))
+                .collect::<Result<_, LoadError>>()?;
+            Ok(Self {
+                withdrawal_credentials: file
+                    .withdrawal_credentials
+                    .unwrap_or_else(|| eth1_withdrawal_credentials(file.withdrawal_vault_address)),
+                withdrawal_credential_prefixes: file
+                    .withdrawal_credential_prefixes
+                    .map(|mut prefixes| {
+                        prefixes.sort_unstable();
+                        prefixes.dedup();
+                        prefixes
+                    })
+                    .unwrap_or_else(lido_withdrawal_credential_prefixes),
+                withdrawal_vault_address: file.withdrawal_vault_address,
+                chain_spec: ChainSpec {
+                    chain_id: file.chain.chain_id,
+                    forks,
+                },
+                genesis_time: file.genesis_time,
+                preset: file.preset,
+                capella_fork_slot: file.capella_fork_slot,
+                deneb_fork_slot: file.deneb_fork_slot,
+                electra_fork_slot: file.electra_fork_slot,
+                fulu_fork_slot: file.fulu_fork_slot,
+                slots_per_historical_root: file
+                    .slots_per_historical_root
+                    .unwrap_or_else(|| preset_slots_per_historical_root(file.preset)),
+                seconds_per_slot: file.seconds_per_slot,
+            })
+        }
+    }
+
+    impl NetworkConfig {
+        pub fn from_json_str(s: &str) -> Result<Self, LoadError> {
+            serde_json::from_str::<NetworkConfigFile>(s)?.try_into()
+        }
+
+        pub fn from_toml_str(s: &str) -> Result<Self, LoadError> {
+            toml::from_str::<NetworkConfigFile>(s)?.try_into()
+        }
+
+        /// Loads a network config from a `.toml` or `.json` file
+        pub fn load(path: impl AsRef<Path>) -> Result<Self, LoadError> {
+            let path = path.as_ref();
+            let contents = std::fs::read_to_string(path)?;
+            match path.extension().and_then(|ext| ext.to_str()) {
+                Some("toml") => Self::from_toml_str(&contents),
+                Some("json") => Self::from_json_str(&contents),
+                ext => Err(LoadError::UnsupportedExtension(ext.map(str::to_string))),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use gindices::Layout;
+
+        #[test]
+        fn toml_and_json_agree() -> Result<(), LoadError> {
+            let from_toml = NetworkConfig::from_toml_str(
+                r#"
+                withdrawal_vault_address = "0xb9d7934878b5fb9610b3fe8a5e441e8fad7e293f"
+                genesis_time = 1606824023
+                capella_fork_slot = 6209536
+                deneb_fork_slot = 8626176
+                electra_fork_slot = 11649024
+                fulu_fork_slot = 13164544
+
+                [chain]
+                chain_id = 31337
+                forks = { prague = 0 }
+                "#,
+            )?;
+            let from_json = NetworkConfig::from_json_str(
+                r#"{
+                    "withdrawal_vault_address": "0xb9d7934878b5fb9610b3fe8a5e441e8fad7e293f",
+                    "genesis_time": 1606824023,
+                    "capella_fork_slot": 6209536,
+                    "deneb_fork_slot": 8626176,
+                    "electra_fork_slot": 11649024,
+                    "fulu_fork_slot": 13164544,
+                    "chain": { "chain_id": 31337, "forks": { "prague": 0 } }
+                }"#,
+            )?;
+            assert_eq!(from_toml, from_json);
+            assert_eq!(from_toml, *ANVIL);
+            Ok(())
+        }
+
+        #[test]
+        fn minimal_preset() -> Result<(), LoadError> {
+            let config = NetworkConfig::from_toml_str(
+                r#"
+                withdrawal_vault_address = "0xb9d7934878b5fb9610b3fe8a5e441e8fad7e293f"
+                genesis_time = 0
+                seconds_per_slot = 6
+                preset = "minimal"
+                capella_fork_slot = 0
+                deneb_fork_slot = 0
+                electra_fork_slot = 0
+
+                [chain]
+                chain_id = 3151908
+                forks = { cancun = 0, prague = 0 }
+                "#,
+            )?;
+            assert_eq!(config.preset, ConsensusPreset::Minimal);
+            assert_eq!(config.slots_per_historical_root, 64);
+            assert_eq!(config.fulu_fork_slot, None);
+            assert_eq!(config.fork_at_slot(1_000_000), Some(Fork::Electra));
+            assert_eq!(
+                config.beacon_state_gindices(Fork::Electra),
+                BeaconStateGindices::new::<Minimal>(Layout::PostElectra, 0)
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn unknown_fork() {
+            let res = NetworkConfig::from_json_str(
+                r#"{
+                    "withdrawal_vault_address": "0xb9d7934878b5fb9610b3fe8a5e441e8fad7e293f",
+                    "genesis_time": 0,
+                    "capella_fork_slot": 0,
+                    "deneb_fork_slot": 0,
+                    "chain": { "chain_id": 1, "forks": { "osaka": 0 } }
+                }"#,
+            );
+            assert!(matches!(res, Err(LoadError::UnknownFork(_))));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compounding_credentials_match() {
+        let mut credentials = MAINNET.withdrawal_credentials;
+        assert!(MAINNET.is_member(credentials.as_slice()));
+        credentials[0] = COMPOUNDING_WITHDRAWAL_PREFIX;
+        assert!(MAINNET.is_member(credentials.as_slice()));
+        credentials[0] = 0x03;
+        assert!(!MAINNET.is_member(credentials.as_slice()));
+        credentials[0] = COMPOUNDING_WITHDRAWAL_PREFIX;
+        credentials[31] ^= 1;
+        assert!(!MAINNET.is_member(credentials.as_slice()));
+    }
+
+    #[test]
+    fn credential_set_is_canonical() {
+        let a = eth1_withdrawal_credentials(MAINNET.withdrawal_vault_address);
+        let b = eth1_withdrawal_credentials(SEPOLIA.withdrawal_vault_address);
+        let set = CredentialSet::new([b, a, b]);
+        assert!(set.is_canonical());
+        assert_eq!(set, CredentialSet::new([a, b]));
+        assert_eq!(set.hash(), CredentialSet::new([a, b]).hash());
+        assert_ne!(set.hash(), CredentialSet::new([a]).hash());
+        assert!(!CredentialSet(vec![b, a]).is_canonical());
+    }
+
+    #[test]
+    fn mainnet_fork_schedule() {
+        assert_eq!(MAINNET.fork_at_slot(6209535), None);
+        assert_eq!(MAINNET.fork_at_slot(6209536), Some(Fork::Capella));
+        assert_eq!(MAINNET.fork_at_slot(8626175), Some(Fork::Capella));
+        assert_eq!(MAINNET.fork_at_slot(8626176), Some(Fork::Deneb));
+        assert_eq!(MAINNET.fork_at_slot(11649023), Some(Fork::Deneb));
+        assert_eq!(MAINNET.fork_at_slot(11649024), Some(Fork::Electra));
+        assert_eq!(MAINNET.fork_at_slot(13164544), Some(Fork::Fulu));
+    }
+
+    #[test]
+    fn mainnet_credentials() {
+        assert_eq!(
+            MAINNET.withdrawal_credentials,
+            B256::new([
+                0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xb9, 0xd7,
+                0x93, 0x48, 0x78, 0xb5, 0xfb, 0x96, 0x10, 0xb3, 0xfe, 0x8a, 0x5e, 0x44, 0x1e, 0x8f,
+                0xad, 0x7e, 0x29, 0x3f,
+            ])
+        );
+    }
+}
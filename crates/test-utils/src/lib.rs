@@ -1,10 +1,14 @@
-use beacon_state::mainnet::ElectraBeaconState;
+use alloy_primitives::B256;
+use beacon_state::{
+    electra::{PendingConsolidation, PendingDeposit, PendingPartialWithdrawal},
+    mainnet::ElectraBeaconState,
+};
 use ethereum_consensus::capella::presets::mainnet::{
     HistoricalBatch, HistoricalSummary, Validator,
 };
+use ethereum_consensus::deneb::presets::mainnet::BeaconState as DenebBeaconState;
 use ethereum_consensus::ssz::prelude::*;
-use gindices::presets::mainnet::beacon_state::{CAPELLA_FORK_SLOT, SLOTS_PER_HISTORICAL_ROOT};
-use guest_io::mainnet::WITHDRAWAL_CREDENTIALS;
+use guest_io::network::{COMPOUNDING_WITHDRAWAL_PREFIX, MAINNET};
 
 pub struct TestStateBuilder {
     inner: ElectraBeaconState,
@@ -28,15 +32,89 @@ impl TestStateBuilder {
     }
 
     pub fn with_lido_validators(&mut self, n_lido_validators: usize) {
+        self.with_credentialed_validators(n_lido_validators, MAINNET.withdrawal_credentials);
+    }
+
+    /// Adds Lido validators that have switched to 0x02 compounding withdrawal credentials
+    pub fn with_compounding_lido_validators(&mut self, n_lido_validators: usize) {
+        let mut withdrawal_credentials = MAINNET.withdrawal_credentials;
+        withdrawal_credentials[0] = COMPOUNDING_WITHDRAWAL_PREFIX;
+        self.with_credentialed_validators(n_lido_validators, withdrawal_credentials);
+    }
+
+    /// Adds slashed Lido validators that become withdrawable at the given epoch
+    pub fn with_slashed_lido_validators(
+        &mut self,
+        n_lido_validators: usize,
+        withdrawable_epoch: u64,
+    ) {
         for _ in 0..n_lido_validators {
             self.inner.validators.push(Validator {
-                withdrawal_credentials: WITHDRAWAL_CREDENTIALS.as_slice().try_into().unwrap(),
+                withdrawal_credentials: MAINNET
+                    .withdrawal_credentials
+                    .as_slice()
+                    .try_into()
+                    .unwrap(),
+                slashed: true,
+                withdrawable_epoch,
+                ..Default::default()
+            });
+            self.inner.balances.push(10);
+        }
+    }
+
+    /// Adds validators with the given withdrawal credentials
+    pub fn with_credentialed_validators(
+        &mut self,
+        n_validators: usize,
+        withdrawal_credentials: B256,
+    ) {
+        for _ in 0..n_validators {
+            self.inner.validators.push(Validator {
+                withdrawal_credentials: withdrawal_credentials.as_slice().try_into().unwrap(),
                 ..Default::default()
             });
             self.inner.balances.push(10);
         }
     }
 
+    /// Adds deposits with the given withdrawal credentials to the pending deposits queue
+    pub fn with_pending_deposits(
+        &mut self,
+        n_deposits: usize,
+        withdrawal_credentials: B256,
+        amount: u64,
+    ) {
+        for _ in 0..n_deposits {
+            self.inner.pending_deposits.push(PendingDeposit {
+                withdrawal_credentials: withdrawal_credentials.as_slice().try_into().unwrap(),
+                amount,
+                ..Default::default()
+            });
+        }
+    }
+
+    /// Adds a pending partial withdrawal from the validator at the given index
+    pub fn with_pending_partial_withdrawal(&mut self, validator_index: usize, amount: u64) {
+        self.inner
+            .pending_partial_withdrawals
+            .push(PendingPartialWithdrawal {
+                validator_index,
+                amount,
+                ..Default::default()
+            });
+    }
+
+    /// Adds a pending consolidation between the validators at the given indices
+    pub fn with_pending_consolidation(&mut self, source_index: usize, target_index: usize) {
+        self.inner
+            .pending_consolidations
+            .push(PendingConsolidation {
+                source_index,
+                target_index,
+            });
+    }
+
     pub fn with_prior_state(
         &mut self,
         prior_state: &beacon_state::mainnet::BeaconState,
@@ -44,10 +122,11 @@ impl TestStateBuilder {
         let slot = self.inner.slot;
         let prior_slot = prior_state.slot();
         assert!(slot > prior_slot, "prior_state.slot must be less than slot");
-        let index: usize = (prior_slot % SLOTS_PER_HISTORICAL_ROOT).try_into().unwrap();
+        let slots_per_historical_root = MAINNET.slots_per_historical_root;
+        let index: usize = (prior_slot % slots_per_historical_root).try_into().unwrap();
 
         // if a short range add the state root to the state_roots list
-        if slot <= prior_slot + SLOTS_PER_HISTORICAL_ROOT {
+        if slot <= prior_slot + slots_per_historical_root {
             self.inner.state_roots[index] = prior_state.hash_tree_root().unwrap();
             None
         } else {
@@ -58,16 +137,37 @@ impl TestStateBuilder {
                 block_summary_root: batch.block_roots.hash_tree_root().unwrap(),
                 state_summary_root: batch.state_roots.hash_tree_root().unwrap(),
             };
-            self.inner.historical_summaries.extend(
-                std::iter::repeat(summary).take(
-                    ((prior_slot - CAPELLA_FORK_SLOT) / SLOTS_PER_HISTORICAL_ROOT) as usize + 1,
-                ),
-            );
+            self.inner
+                .historical_summaries
+                .extend(std::iter::repeat(summary).take(
+                    ((prior_slot - MAINNET.capella_fork_slot) / slots_per_historical_root) as usize
+                        + 1,
+                ));
             Some(batch)
         }
     }
 
+    /// Sets the hash of the execution block in the latest beacon block
+    pub fn with_execution_block_hash(&mut self, block_hash: B256) {
+        self.inner.latest_execution_payload_header.block_hash =
+            block_hash.as_slice().try_into().unwrap();
+    }
+
     pub fn build(self) -> beacon_state::mainnet::BeaconState {
         beacon_state::mainnet::BeaconState::Electra(self.inner)
     }
+
+    /// Builds a pre-Electra (Deneb) state with the same slot, validators, balances and roots.
+    /// Anything added to the Electra only pending queues is dropped.
+    pub fn build_deneb(self) -> beacon_state::mainnet::BeaconState {
+        beacon_state::mainnet::BeaconState::Deneb(DenebBeaconState {
+            slot: self.inner.slot,
+            state_roots: self.inner.state_roots,
+            validators: self.inner.validators,
+            balances: self.inner.balances,
+            historical_summaries: self.inner.historical_summaries,
+            latest_execution_payload_header: self.inner.latest_execution_payload_header,
+            ..Default::default()
+        })
+    }
 }
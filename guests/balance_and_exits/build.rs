@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{collections::HashMap, env};
+use std::{collections::HashMap, env, path::Path};
 
 use risc0_build::{embed_methods_with_options, GuestOptionsBuilder};
 use risc0_build_ethereum::generate_solidity_files;
@@ -32,6 +32,19 @@ fn main() {
         guest_features.push("skip-verify".to_string());
     }
 
+    // The custom guest embeds the network config with include_str!, which resolves relative
+    // paths against the guest source file
+    println!("cargo:rerun-if-env-changed=CUSTOM_NETWORK_CONFIG");
+    if let Some(config) = env::var_os("CUSTOM_NETWORK_CONFIG") {
+        let config = Path::new(&config);
+        assert!(
+            config.is_absolute() && config.is_file(),
+            "CUSTOM_NETWORK_CONFIG must be the absolute path of a network config file"
+        );
+        println!("cargo:rerun-if-changed={}", config.display());
+        guest_features.push("custom-network".to_string());
+    }
+
     println!(
         "cargo:warning=building guest with features: {:?}",
         guest_features
@@ -46,6 +59,14 @@ fn main() {
             .unwrap(),
     )]));
 
+    // custom network ELFs are passed to the CLI with --balance-and-exits-elf
+    for guest in guests.iter().filter(|guest| guest.name == "custom") {
+        println!(
+            "cargo:warning=custom network balance and exits ELF: {}",
+            guest.path
+        );
+    }
+
     // Generate Solidity source files for use with Forge.
     let solidity_opts = risc0_build_ethereum::Options::default()
         .with_image_id_sol_path(SOLIDITY_IMAGE_ID_PATH)
@@ -0,0 +1,20 @@
+#![no_main]
+
+use guest_io::NetworkConfig;
+
+risc0_zkvm::guest::entry!(main);
+
+/// Config of the network this program is built for, embedded when the builder is compiled
+/// with `CUSTOM_NETWORK_CONFIG` set. The config is part of the image ID.
+const NETWORK_CONFIG_PATH: &str = env!("CUSTOM_NETWORK_CONFIG");
+const NETWORK_CONFIG: &str = include_str!(env!("CUSTOM_NETWORK_CONFIG"));
+
+fn main() {
+    let network = if NETWORK_CONFIG_PATH.ends_with(".json") {
+        NetworkConfig::from_json_str(NETWORK_CONFIG)
+    } else {
+        NetworkConfig::from_toml_str(NETWORK_CONFIG)
+    }
+    .expect("invalid custom network config");
+    balance_and_exits::entry(&network, membership_builder::CUSTOM_ID);
+}
@@ -0,0 +1,9 @@
+#![no_main]
+
+use guest_io::network::HOLESKY;
+
+risc0_zkvm::guest::entry!(main);
+
+fn main() {
+    balance_and_exits::entry(&HOLESKY, membership_builder::HOLESKY_ID);
+}
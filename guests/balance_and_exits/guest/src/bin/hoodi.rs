@@ -0,0 +1,9 @@
+#![no_main]
+
+use guest_io::network::HOODI;
+
+risc0_zkvm::guest::entry!(main);
+
+fn main() {
+    balance_and_exits::entry(&HOODI, membership_builder::HOODI_ID);
+}
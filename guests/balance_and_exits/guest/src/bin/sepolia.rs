@@ -1,14 +1,9 @@
 #![no_main]
 
-use guest_io::sepolia::WITHDRAWAL_VAULT_ADDRESS;
-use risc0_steel::ethereum::ETH_SEPOLIA_CHAIN_SPEC;
+use guest_io::network::SEPOLIA;
 
 risc0_zkvm::guest::entry!(main);
 
 fn main() {
-    balance_and_exits::entry(
-        &ETH_SEPOLIA_CHAIN_SPEC,
-        WITHDRAWAL_VAULT_ADDRESS,
-        membership_builder::SEPOLIA_ID,
-    );
+    balance_and_exits::entry(&SEPOLIA, membership_builder::SEPOLIA_ID);
 }
@@ -12,36 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use alloy_primitives::{Address, U256};
+use alloy_primitives::U256;
 use alloy_sol_types::SolValue;
 use bincode::deserialize;
 use bitvec::prelude::*;
 use bitvec::vec::BitVec;
 use gindices::presets::mainnet::beacon_block as beacon_block_gindices;
-use gindices::presets::mainnet::beacon_state::post_electra as beacon_state_gindices;
-use guest_io::balance_and_exits::{Input, Journal};
+use gindices::BeaconStateGindices;
+use guest_io::balance_and_exits::{AuxiliaryReport, Input, Journal};
 use guest_io::validator_membership::Journal as MembershipJounal;
-use guest_io::InputWithReceipt;
-use risc0_steel::ethereum::EthChainSpec;
+use guest_io::{Fork, InputWithReceipt, NetworkConfig};
 use risc0_steel::Account;
 use risc0_zkvm::guest::env;
 use risc0_zkvm::Receipt;
-use ssz_multiproofs::ValueIterator;
+use ssz_multiproofs::{Multiproof, ValueIterator};
 
 type Node = [u8; 32];
 
-// #[cfg(feature = "anvil")]
-// use guest_io::ANVIL_CHAIN_SPEC as CHAIN_SPEC;
-// #[cfg(not(feature = "sepolia"))]
-// use risc0_steel::ethereum::ETH_MAINNET_CHAIN_SPEC as CHAIN_SPEC;
-// #[cfg(feature = "sepolia")]
-// use risc0_steel::ethereum::ETH_SEPOLIA_CHAIN_SPEC as CHAIN_SPEC;
-
-pub fn entry(
-    spec: &EthChainSpec,
-    withdrawal_vault_address: Address,
-    membership_program_id: [u32; 8],
-) {
+pub fn entry(network: &NetworkConfig, membership_program_id: [u32; 8]) {
     env::log("Reading input");
     let input_bytes = env::read_frame();
 
@@ -50,6 +38,7 @@ pub fn entry(
         input:
             Input {
                 block_root,
+                fork,
                 membership,
                 block_multiproof,
                 state_multiproof: multiproof,
@@ -59,8 +48,8 @@ pub fn entry(
     } = deserialize(&input_bytes).expect("Failed to deserialize input");
 
     // obtain the withdrawal vault balance from the EVM input
-    let evm_env = evm_input.into_env(spec);
-    let account = Account::new(withdrawal_vault_address, &evm_env);
+    let evm_env = evm_input.into_env(&network.chain_spec);
+    let account = Account::new(network.withdrawal_vault_address, &evm_env);
     let withdrawal_vault_balance: U256 = account.info().balance;
 
     env::log("Verifying block multiproof");
@@ -72,6 +61,17 @@ pub fn entry(
     let slot = get_slot(&mut block_values);
     let state_root = get_state_root(&mut block_values);
 
+    // the fork is committed to the journal so it must be the fork of the proven state, not just
+    // one with the same layout
+    assert_eq!(
+        network.fork_at_slot(slot),
+        Some(fork),
+        "Fork {fork:?} is not active at slot {slot}"
+    );
+    // Capella states have a smaller execution payload header than the gindices describe
+    assert!(fork >= Fork::Deneb, "Fork {fork:?} is not supported");
+    let beacon_state_gindices = network.beacon_state_gindices(fork);
+
     env::log("Verifying state multiproof");
     multiproof
         .verify(&state_root)
@@ -79,19 +79,59 @@ pub fn entry(
     let mut values = multiproof.values();
 
     // Compute the required values from the beacon state values
-    env::log("Computing validator count, balances, exited validators");
+    env::log("Computing validator count, balances, exited and slashed validators");
     let num_validators = membership.count_ones() as u64;
-    let num_exited_validators = count_exited_validators(&mut values, &membership, slot);
-    let validator_count = get_validator_count(&mut values);
-    let cl_balance = accumulate_balances(&mut values, &membership);
+    let member_status = read_member_status(
+        &beacon_state_gindices,
+        &mut values,
+        &membership,
+        slot / network.preset.slots_per_epoch(),
+    );
+    let validator_count = get_validator_count(&beacon_state_gindices, &mut values);
+    let (cl_balance, slashed_balance) = accumulate_balances(
+        &beacon_state_gindices,
+        &mut values,
+        &membership,
+        &member_status.slashed_not_withdrawable,
+    );
+
+    // The state is the post-state of the proven beacon block, so its latest execution payload
+    // header is the payload of that block. Steel must read the vault balance at that block.
+    // Block processing copies body.execution_payload.block_hash into this header and the state
+    // root is proven against the block root above, so this binds the same hash as a proof into
+    // the block body would, without needing the block body, which archived states do not carry.
+    env::log("Verifying the execution block is the payload of the beacon block");
+    let execution_block_hash = values
+        .next_assert_gindex(beacon_state_gindices.latest_execution_payload_header_block_hash())
+        .unwrap();
+    assert_eq!(
+        evm_env.header().seal().as_slice(),
+        execution_block_hash,
+        "Steel execution block is not the payload of beacon block {block_root}"
+    );
+
+    env::log("Computing pending deposits, partial withdrawals and consolidations");
+    let pending = if beacon_state_gindices.has_pending_queues() {
+        read_pending_queues(
+            &beacon_state_gindices,
+            &multiproof,
+            &mut values,
+            network,
+            &membership,
+        )
+    } else {
+        PendingQueues::default()
+    };
 
     // verify the membership proof
     #[cfg(not(feature = "skip-verify"))]
     {
         env::log("Verifying validator membership proof");
         verify_membership(
+            network,
             membership_program_id,
             state_root,
+            fork,
             membership,
             validator_count,
             membership_receipt.expect("No membership receipt provided"),
@@ -103,23 +143,39 @@ pub fn entry(
         clBalanceGwei: U256::from(cl_balance),
         withdrawalVaultBalanceWei: withdrawal_vault_balance.into(),
         totalDepositedValidators: U256::from(num_validators),
-        totalExitedValidators: U256::from(num_exited_validators),
+        totalExitedValidators: U256::from(member_status.exited),
+        auxiliary: AuxiliaryReport {
+            pendingDepositsGwei: U256::from(pending.deposits),
+            pendingPartialWithdrawalsGwei: U256::from(pending.partial_withdrawals),
+            pendingConsolidationsFromLido: U256::from(pending.consolidations_from_members),
+            pendingConsolidationsToLido: U256::from(pending.consolidations_to_members),
+            slashedValidators: U256::from(member_status.slashed),
+            slashedBalanceGwei: U256::from(slashed_balance),
+        },
         blockRoot: block_root.into(),
+        slot: U256::from(slot),
+        stateRoot: (*state_root).into(),
+        fork: fork as u8,
         commitment: evm_env.into_commitment(),
     };
     env::commit_slice(&journal.abi_encode());
 }
 
 fn verify_membership(
+    network: &NetworkConfig,
     membership_program_id: [u32; 8],
     state_root: &Node,
+    fork: Fork,
     membership: BitVec<u32, Lsb0>,
     validator_count: u64,
     membership_receipt: Receipt,
 ) {
     let j = MembershipJounal {
         self_program_id: membership_program_id.into(),
+        withdrawal_credential_prefixes: network.withdrawal_credential_prefixes.clone(),
+        withdrawal_credentials_hash: network.credential_set().hash(),
         state_root: state_root.clone().into(),
+        fork,
         membership: membership,
         max_validator_index: validator_count - 1,
     };
@@ -146,45 +202,75 @@ fn get_state_root<'a, I: Iterator<Item = (u64, &'a Node)>>(
 }
 
 fn get_validator_count<'a, I: Iterator<Item = (u64, &'a Node)>>(
+    beacon_state_gindices: &BeaconStateGindices,
     values: &mut ValueIterator<'a, I, 32>,
 ) -> u64 {
     let validator_count = values
-        .next_assert_gindex(beacon_state_gindices::validator_count())
+        .next_assert_gindex(beacon_state_gindices.validator_count())
         .unwrap();
     u64_from_b256(validator_count, 0)
 }
 
-fn count_exited_validators<'a, I: Iterator<Item = (u64, &'a Node)>>(
+/// Exit and slashing status of the member validators
+struct MemberStatus {
+    exited: u64,
+    slashed: u64,
+    /// Member validators that are slashed and not yet withdrawable, indexed by validator index
+    slashed_not_withdrawable: BitVec<u32, Lsb0>,
+}
+
+fn read_member_status<'a, I: Iterator<Item = (u64, &'a Node)>>(
+    beacon_state_gindices: &BeaconStateGindices,
     values: &mut ValueIterator<'a, I, 32>,
     membership: &BitVec<u32, Lsb0>,
-    slot: u64,
-) -> u64 {
-    let current_epoch = slot / 32;
-    let mut num_exited_validators = 0;
-    // Iterate the validator exit epochs
+    current_epoch: u64,
+) -> MemberStatus {
+    let mut status = MemberStatus {
+        exited: 0,
+        slashed: 0,
+        slashed_not_withdrawable: BitVec::repeat(false, membership.len()),
+    };
+    // Iterate the validator fields. These are ordered as they appear in the Validator container
     for validator_index in membership.iter_ones() {
-        let value = values
-            .next_assert_gindex(beacon_state_gindices::validator_exit_epoch(
-                validator_index as u64,
-            ))
+        let slashed = values
+            .next_assert_gindex(beacon_state_gindices.validator_slashed(validator_index as u64))
             .unwrap();
-        if u64_from_b256(&value, 0) <= current_epoch {
-            num_exited_validators += 1;
+        let exit_epoch = values
+            .next_assert_gindex(beacon_state_gindices.validator_exit_epoch(validator_index as u64))
+            .unwrap();
+        let withdrawable_epoch = values
+            .next_assert_gindex(
+                beacon_state_gindices.validator_withdrawable_epoch(validator_index as u64),
+            )
+            .unwrap();
+        if u64_from_b256(&exit_epoch, 0) <= current_epoch {
+            status.exited += 1;
+        }
+        // booleans are serialized as a single byte
+        if slashed[0] == 1 {
+            status.slashed += 1;
+            if u64_from_b256(&withdrawable_epoch, 0) > current_epoch {
+                status.slashed_not_withdrawable.set(validator_index, true);
+            }
         }
     }
-    num_exited_validators
+    status
 }
 
+/// Returns the total balance of the member validators and of the slashed, not yet withdrawable, member validators
 fn accumulate_balances<'a, I: Iterator<Item = (u64, &'a Node)>>(
+    beacon_state_gindices: &BeaconStateGindices,
     values: &mut ValueIterator<'a, I, 32>,
     membership: &BitVec<u32, Lsb0>,
-) -> u64 {
+    slashed_not_withdrawable: &BitVec<u32, Lsb0>,
+) -> (u64, u64) {
     // accumulate the balances but iterating over the membership bitvec
     // This is a little tricky as multiple balances are packed into a single gindex
     let mut cl_balance = 0;
+    let mut slashed_balance = 0;
     let mut current_leaf = (0, &[0_u8; 32]); // 0 is an invalid gindex so this will always be updated on the first validator
     for validator_index in membership.iter_ones() {
-        let expeted_gindex = beacon_state_gindices::validator_balance(validator_index as u64);
+        let expeted_gindex = beacon_state_gindices.validator_balance(validator_index as u64);
         if current_leaf.0 != expeted_gindex {
             current_leaf = values.next().expect(&format!(
                 "Missing valdator {} balance value in multiproof",
@@ -194,8 +280,172 @@ fn accumulate_balances<'a, I: Iterator<Item = (u64, &'a Node)>>(
         assert_eq!(current_leaf.0, expeted_gindex);
         let balance = u64_from_b256(&current_leaf.1, validator_index as usize % 4);
         cl_balance += balance;
+        if slashed_not_withdrawable[validator_index] {
+            slashed_balance += balance;
+        }
+    }
+    (cl_balance, slashed_balance)
+}
+
+/// Figures from the pending deposit, partial withdrawal and consolidation queues added in Electra
+#[derive(Default)]
+struct PendingQueues {
+    deposits: u64,
+    partial_withdrawals: u64,
+    consolidations_from_members: u64,
+    consolidations_to_members: u64,
+}
+
+fn read_pending_queues<'a, I: Iterator<Item = (u64, &'a Node)>>(
+    beacon_state_gindices: &BeaconStateGindices,
+    multiproof: &Multiproof,
+    values: &mut ValueIterator<'a, I, 32>,
+    network: &NetworkConfig,
+    membership: &BitVec<u32, Lsb0>,
+) -> PendingQueues {
+    let pending_deposits_count =
+        get_list_length(multiproof, beacon_state_gindices.pending_deposits_count());
+    let deposits = accumulate_pending_deposits(
+        beacon_state_gindices,
+        values,
+        network,
+        pending_deposits_count,
+    );
+
+    let pending_partial_withdrawals_count = get_list_length(
+        multiproof,
+        beacon_state_gindices.pending_partial_withdrawals_count(),
+    );
+    let partial_withdrawals = accumulate_pending_partial_withdrawals(
+        beacon_state_gindices,
+        values,
+        membership,
+        pending_partial_withdrawals_count,
+    );
+
+    let pending_consolidations_count = get_list_length(
+        multiproof,
+        beacon_state_gindices.pending_consolidations_count(),
+    );
+    let (consolidations_from_members, consolidations_to_members) = count_pending_consolidations(
+        beacon_state_gindices,
+        values,
+        membership,
+        pending_consolidations_count,
+    );
+
+    PendingQueues {
+        deposits,
+        partial_withdrawals,
+        consolidations_from_members,
+        consolidations_to_members,
     }
-    cl_balance
+}
+
+fn get_list_length(multiproof: &Multiproof, length_gindex: u64) -> u64 {
+    // The length of a list is stored after its items so it must be looked up before iterating them
+    let length = multiproof
+        .get(length_gindex)
+        .expect("Missing list length in multiproof");
+    u64_from_b256(&length, 0)
+}
+
+fn accumulate_pending_deposits<'a, I: Iterator<Item = (u64, &'a Node)>>(
+    beacon_state_gindices: &BeaconStateGindices,
+    values: &mut ValueIterator<'a, I, 32>,
+    network: &NetworkConfig,
+    pending_deposits_count: u64,
+) -> u64 {
+    // Every pending deposit must be checked so that none matching the Lido credentials can be omitted
+    let mut pending_deposits = 0;
+    for deposit_index in 0..pending_deposits_count {
+        let withdrawal_credentials = values
+            .next_assert_gindex(
+                beacon_state_gindices.pending_deposit_withdrawal_credentials(deposit_index),
+            )
+            .unwrap();
+        let amount = values
+            .next_assert_gindex(beacon_state_gindices.pending_deposit_amount(deposit_index))
+            .unwrap();
+        if network.is_member(withdrawal_credentials) {
+            pending_deposits += u64_from_b256(amount, 0);
+        }
+    }
+    // skip over the length which follows the items
+    values
+        .next_assert_gindex(beacon_state_gindices.pending_deposits_count())
+        .unwrap();
+    pending_deposits
+}
+
+fn accumulate_pending_partial_withdrawals<'a, I: Iterator<Item = (u64, &'a Node)>>(
+    beacon_state_gindices: &BeaconStateGindices,
+    values: &mut ValueIterator<'a, I, 32>,
+    membership: &BitVec<u32, Lsb0>,
+    pending_partial_withdrawals_count: u64,
+) -> u64 {
+    let mut pending_partial_withdrawals = 0;
+    for withdrawal_index in 0..pending_partial_withdrawals_count {
+        let validator_index = values
+            .next_assert_gindex(
+                beacon_state_gindices.pending_partial_withdrawal_validator_index(withdrawal_index),
+            )
+            .unwrap();
+        let amount = values
+            .next_assert_gindex(
+                beacon_state_gindices.pending_partial_withdrawal_amount(withdrawal_index),
+            )
+            .unwrap();
+        if is_member(membership, u64_from_b256(validator_index, 0)) {
+            pending_partial_withdrawals += u64_from_b256(amount, 0);
+        }
+    }
+    // skip over the length which follows the items
+    values
+        .next_assert_gindex(beacon_state_gindices.pending_partial_withdrawals_count())
+        .unwrap();
+    pending_partial_withdrawals
+}
+
+/// Returns the number of pending consolidations with a member validator as the source and as the target
+fn count_pending_consolidations<'a, I: Iterator<Item = (u64, &'a Node)>>(
+    beacon_state_gindices: &BeaconStateGindices,
+    values: &mut ValueIterator<'a, I, 32>,
+    membership: &BitVec<u32, Lsb0>,
+    pending_consolidations_count: u64,
+) -> (u64, u64) {
+    let mut from_members = 0;
+    let mut to_members = 0;
+    for consolidation_index in 0..pending_consolidations_count {
+        let source_index = values
+            .next_assert_gindex(
+                beacon_state_gindices.pending_consolidation_source_index(consolidation_index),
+            )
+            .unwrap();
+        let target_index = values
+            .next_assert_gindex(
+                beacon_state_gindices.pending_consolidation_target_index(consolidation_index),
+            )
+            .unwrap();
+        if is_member(membership, u64_from_b256(source_index, 0)) {
+            from_members += 1;
+        }
+        if is_member(membership, u64_from_b256(target_index, 0)) {
+            to_members += 1;
+        }
+    }
+    // skip over the length which follows the items
+    values
+        .next_assert_gindex(beacon_state_gindices.pending_consolidations_count())
+        .unwrap();
+    (from_members, to_members)
+}
+
+fn is_member(membership: &BitVec<u32, Lsb0>, validator_index: u64) -> bool {
+    usize::try_from(validator_index)
+        .ok()
+        .and_then(|i| membership.get(i).map(|bit| *bit))
+        .unwrap_or(false)
 }
 
 /// Slice an 8 byte u64 out of a 32 byte chunk
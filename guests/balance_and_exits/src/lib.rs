@@ -17,24 +17,26 @@ include!(concat!(env!("OUT_DIR"), "/methods.rs"));
 
 #[cfg(test)]
 mod tests {
-    use alloy_primitives::utils::parse_ether;
+    use alloy_primitives::{utils::parse_ether, B256};
     use alloy_sol_types::SolValue;
+    use beacon_state::mainnet::BeaconState;
     use ethereum_consensus::phase0::presets::mainnet::BeaconBlockHeader;
     use ethereum_consensus::ssz::prelude::*;
-    use gindices::presets::mainnet::beacon_state::CAPELLA_FORK_SLOT;
     use guest_io::{
         balance_and_exits::{self, Journal},
-        mainnet::WITHDRAWAL_CREDENTIALS,
-        mainnet::WITHDRAWAL_VAULT_ADDRESS,
-        validator_membership, ANVIL_CHAIN_SPEC,
+        network::{ANVIL, MAINNET, SEPOLIA},
+        validator_membership, Fork,
+    };
+    use risc0_steel::{
+        ethereum::{EthEvmEnv, EthEvmInput},
+        Account,
     };
-    use risc0_steel::{ethereum::EthEvmEnv, Account};
     use risc0_zkvm::{default_executor, ExecutorEnv, LocalProver, Prover};
     use test_utils::TestStateBuilder;
 
     use alloy::providers::{ext::AnvilApi, Provider, ProviderBuilder};
 
-    /// Returns an Anvil provider the WITHDRAWAL_VAULT_ADDRESS balance set to 33 ether
+    /// Returns an Anvil provider with the mainnet withdrawal vault balance set to 33 ether
     async fn test_provider() -> impl Provider + Clone {
         let provider = ProviderBuilder::new()
             .connect_anvil_with_wallet_and_config(|anvil| anvil.args(["--hardfork", "cancun"]))
@@ -42,7 +44,7 @@ mod tests {
         let node_info = provider.anvil_node_info().await.unwrap();
         println!("Anvil started: {:?}", node_info);
         provider
-            .anvil_set_balance(WITHDRAWAL_VAULT_ADDRESS, parse_ether("33").unwrap())
+            .anvil_set_balance(MAINNET.withdrawal_vault_address, parse_ether("33").unwrap())
             .await
             .unwrap();
         // mine a block so the new balance is in the state
@@ -50,23 +52,48 @@ mod tests {
         provider
     }
 
-    #[tokio::test(flavor = "multi_thread")]
-    async fn test_balance_and_exits() -> anyhow::Result<()> {
-        let n_validators = 10;
-        let n_lido_validators = 1;
-        let max_validator_index = n_validators + n_lido_validators - 1;
+    /// Builds the Steel input for reading the withdrawal vault balance and returns it together
+    /// with the hash of the execution block it is for
+    async fn steel_input() -> (EthEvmInput, B256) {
+        let provider = test_provider().await;
+        let mut env = EthEvmEnv::builder()
+            .provider(provider.clone())
+            .chain_spec(&ANVIL.chain_spec)
+            .build()
+            .await
+            .unwrap();
+        let preflight_info = {
+            let account = Account::preflight(MAINNET.withdrawal_vault_address, &mut env);
+            account.bytecode(true).info().await.unwrap()
+        };
+        assert_eq!(preflight_info.balance, parse_ether("33").unwrap());
 
-        let mut b = TestStateBuilder::new(CAPELLA_FORK_SLOT);
-        b.with_validators(n_validators);
-        b.with_lido_validators(n_lido_validators);
-        let s = b.build();
+        // Sanity check converting it back to an env as in the guest gives the same account info
+        let input = env.into_input().await.unwrap();
+        let env = input.clone().into_env(&ANVIL.chain_spec);
+        let info = {
+            let account = Account::new(MAINNET.withdrawal_vault_address, &env);
+            account.bytecode(true).info()
+        };
+        assert_eq!(info, preflight_info, "mismatch in preflight and execution");
+
+        (input, env.header().seal())
+    }
 
+    /// Proves membership of the validators in the state and executes the balance and exits program over it
+    async fn execute(
+        s: BeaconState,
+        evm_input: EthEvmInput,
+        max_validator_index: u64,
+    ) -> anyhow::Result<Journal> {
         let mut block_header = BeaconBlockHeader::default();
         block_header.slot = s.slot();
         block_header.state_root = s.hash_tree_root().unwrap();
 
         // build a membership proof
         let input = validator_membership::Input::build_initial(
+            &MAINNET,
+            MAINNET.credential_set(),
             s.clone(),
             max_validator_index as u64,
             membership_builder::MAINNET_ID,
@@ -80,36 +107,8 @@ mod tests {
             LocalProver::new("test").prove(env, membership_builder::MAINNET_ELF)
         })?;
 
-        // build the Steel input for reading the balance
-        let provider = test_provider().await;
-        let mut env = EthEvmEnv::builder()
-            .provider(provider.clone())
-            .chain_spec(&ANVIL_CHAIN_SPEC)
-            .build()
-            .await
-            .unwrap();
-        let preflight_info = {
-            let account = Account::preflight(WITHDRAWAL_VAULT_ADDRESS, &mut env);
-            account.bytecode(true).info().await.unwrap()
-        };
-        assert_eq!(preflight_info.balance, parse_ether("33").unwrap());
-
-        // Sanity check converting it back to an env as in the guest gives the same account info
-        let input = env.into_input().await.unwrap();
-        let env = input.clone().into_env(&ANVIL_CHAIN_SPEC);
-        let info = {
-            let account = Account::new(WITHDRAWAL_VAULT_ADDRESS, &env);
-            account.bytecode(true).info()
-        };
-        assert_eq!(info, preflight_info, "mismatch in preflight and execution");
-
-        let zkvm_input = balance_and_exits::Input::build(
-            WITHDRAWAL_CREDENTIALS,
-            &block_header,
-            &s.clone(),
-            input,
-        )?
-        .with_receipt(membership_proof.receipt);
+        let zkvm_input = balance_and_exits::Input::build(&MAINNET, &block_header, &s, evm_input)?
+            .with_receipt(membership_proof.receipt);
         let env = ExecutorEnv::builder()
             .write_frame(&bincode::serialize(&zkvm_input).unwrap())
             .build()?;
@@ -119,7 +118,35 @@ mod tests {
         println!("program execution returned: {:?}", session_info.journal);
         println!("total cycles: {}", session_info.cycles());
 
-        let journal = Journal::abi_decode(&session_info.journal.bytes).unwrap();
+        Ok(Journal::abi_decode(&session_info.journal.bytes).unwrap())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_balance_and_exits() -> anyhow::Result<()> {
+        let n_validators = 10;
+        let n_lido_validators = 1;
+        let n_slashed_lido_validators = 2;
+        let max_validator_index = n_validators + n_lido_validators + n_slashed_lido_validators - 1;
+
+        let mut b = TestStateBuilder::new(MAINNET.electra_fork_slot.unwrap());
+        b.with_validators(n_validators);
+        b.with_lido_validators(n_lido_validators);
+        // one slashed validator is already withdrawable, the other is not
+        b.with_slashed_lido_validators(1, 0);
+        b.with_slashed_lido_validators(1, u64::MAX);
+        b.with_pending_deposits(2, MAINNET.withdrawal_credentials, 32_000_000_000);
+        b.with_pending_deposits(3, SEPOLIA.withdrawal_credentials, 1_000_000_000);
+        // validator 10 is the Lido validator
+        b.with_pending_partial_withdrawal(10, 5_000_000);
+        b.with_pending_partial_withdrawal(3, 7_000_000);
+        b.with_pending_consolidation(10, 2);
+        b.with_pending_consolidation(4, 10);
+        b.with_pending_consolidation(1, 2);
+        let (evm_input, block_hash) = steel_input().await;
+        b.with_execution_block_hash(block_hash);
+        let s = b.build();
+
+        let journal = execute(s.clone(), evm_input, max_validator_index as u64).await?;
         assert_eq!(
             journal.withdrawalVaultBalanceWei,
             parse_ether("33").unwrap(),
@@ -127,8 +154,74 @@ mod tests {
         );
         assert_eq!(
             journal.totalDepositedValidators,
-            U256::from(n_lido_validators)
+            U256::from(n_lido_validators + n_slashed_lido_validators)
+        );
+        assert_eq!(
+            journal.auxiliary.pendingDepositsGwei,
+            U256::from(64_000_000_000_u64)
+        );
+        assert_eq!(
+            journal.auxiliary.pendingPartialWithdrawalsGwei,
+            U256::from(5_000_000)
+        );
+        assert_eq!(
+            journal.auxiliary.pendingConsolidationsFromLido,
+            U256::from(1)
         );
+        assert_eq!(journal.auxiliary.pendingConsolidationsToLido, U256::from(1));
+        assert_eq!(journal.auxiliary.slashedValidators, U256::from(2));
+        assert_eq!(journal.auxiliary.slashedBalanceGwei, U256::from(10));
+        assert_eq!(journal.slot, U256::from(s.slot()));
+        assert_eq!(journal.stateRoot, s.hash_tree_root().unwrap());
+        assert_eq!(journal.fork, Fork::Electra as u8);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_balance_and_exits_pre_electra() -> anyhow::Result<()> {
+        let n_validators = 10;
+        let n_lido_validators = 3;
+        let n_slashed_lido_validators = 1;
+        let max_validator_index = n_validators + n_lido_validators + n_slashed_lido_validators - 1;
+
+        let mut b = TestStateBuilder::new(MAINNET.deneb_fork_slot);
+        b.with_validators(n_validators);
+        b.with_lido_validators(n_lido_validators);
+        b.with_slashed_lido_validators(n_slashed_lido_validators, u64::MAX);
+        let (evm_input, block_hash) = steel_input().await;
+        b.with_execution_block_hash(block_hash);
+        let s = b.build_deneb();
+
+        let journal = execute(s.clone(), evm_input, max_validator_index as u64).await?;
+        assert_eq!(
+            journal.totalDepositedValidators,
+            U256::from(n_lido_validators + n_slashed_lido_validators)
+        );
+        assert_eq!(journal.clBalanceGwei, U256::from(40));
+        assert_eq!(journal.auxiliary.pendingDepositsGwei, U256::ZERO);
+        assert_eq!(journal.auxiliary.pendingPartialWithdrawalsGwei, U256::ZERO);
+        assert_eq!(journal.auxiliary.slashedValidators, U256::from(1));
+        assert_eq!(journal.auxiliary.slashedBalanceGwei, U256::from(10));
+        assert_eq!(journal.fork, Fork::Deneb as u8);
+        Ok(())
+    }
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_execution_block_must_be_in_beacon_block() -> anyhow::Result<()> {
+        let mut b = TestStateBuilder::new(MAINNET.electra_fork_slot.unwrap());
+        b.with_lido_validators(1);
+        // the Steel block is not the payload of the beacon block
+        b.with_execution_block_hash(B256::repeat_byte(0xab));
+        let s = b.build();
+        let mut block_header = BeaconBlockHeader::default();
+        block_header.slot = s.slot();
+        block_header.state_root = s.hash_tree_root().unwrap();
+
+        let (evm_input, _) = steel_input().await;
+        let result = balance_and_exits::Input::build(&MAINNET, &block_header, &s, evm_input);
+        assert!(matches!(
+            result,
+            Err(guest_io::Error::ExecutionBlockMismatch { .. })
+        ));
         Ok(())
     }
 }
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{collections::HashMap, env};
+use std::{collections::HashMap, env, path::Path};
 
 use risc0_build::{embed_methods_with_options, GuestOptionsBuilder};
 
@@ -23,17 +23,38 @@ fn main() {
         guest_features.push("skip-verify".to_string());
     }
 
+    // The custom guest embeds the network config with include_str!, which resolves relative
+    // paths against the guest source file
+    println!("cargo:rerun-if-env-changed=CUSTOM_NETWORK_CONFIG");
+    if let Some(config) = env::var_os("CUSTOM_NETWORK_CONFIG") {
+        let config = Path::new(&config);
+        assert!(
+            config.is_absolute() && config.is_file(),
+            "CUSTOM_NETWORK_CONFIG must be the absolute path of a network config file"
+        );
+        println!("cargo:rerun-if-changed={}", config.display());
+        guest_features.push("custom-network".to_string());
+    }
+
     println!(
         "cargo:warning=building guest with features: {:?}",
         guest_features
     );
 
     // Generate Rust source files for the methods crate.
-    embed_methods_with_options(HashMap::from([(
+    let guests = embed_methods_with_options(HashMap::from([(
         "validator_membership",
         GuestOptionsBuilder::default()
             .features(guest_features)
             .build()
             .unwrap(),
     )]));
+
+    // custom network ELFs are passed to the CLI with --membership-elf
+    for guest in guests.iter().filter(|guest| guest.name == "custom") {
+        println!(
+            "cargo:warning=custom network membership ELF: {}",
+            guest.path
+        );
+    }
 }
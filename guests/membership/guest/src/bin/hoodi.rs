@@ -0,0 +1,9 @@
+#![no_main]
+
+use guest_io::network::HOODI;
+
+risc0_zkvm::guest::entry!(main);
+
+fn main() {
+    validator_membership::entry(&HOODI);
+}
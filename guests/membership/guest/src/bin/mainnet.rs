@@ -1,9 +1,9 @@
 #![no_main]
 
-use guest_io::mainnet::WITHDRAWAL_CREDENTIALS;
+use guest_io::network::MAINNET;
 
 risc0_zkvm::guest::entry!(main);
 
 fn main() {
-    validator_membership::entry(WITHDRAWAL_CREDENTIALS);
+    validator_membership::entry(&MAINNET);
 }
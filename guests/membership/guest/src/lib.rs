@@ -15,16 +15,15 @@
 use alloy_primitives::B256;
 use bincode::deserialize;
 use bitvec::prelude::*;
-use gindices::presets::mainnet::beacon_state::post_electra as beacon_state_gindices;
-use gindices::presets::mainnet::historical_batch as historical_batch_gindices;
 use guest_io::validator_membership::{
+    ChunkJournal,
     ContinuationType::{LongRange, SameSlot, ShortRange},
     Input, Journal, ProofType,
 };
-use guest_io::InputWithReceipt;
-use risc0_zkvm::guest::env;
+use guest_io::{Fork, InputWithReceipt, NetworkConfig};
+use risc0_zkvm::{guest::env, sha::Digest, Receipt};
 
-pub fn entry(withdrawal_credentials: B256) {
+pub fn entry(network: &NetworkConfig) {
     env::log("Reading input");
     let input_bytes = env::read_frame();
 
@@ -34,8 +33,10 @@ pub fn entry(withdrawal_credentials: B256) {
             Input {
                 multiproof,
                 state_root,
+                fork,
                 proof_type,
                 self_program_id,
+                withdrawal_credentials,
                 max_validator_index,
                 hist_summary_multiproof,
             },
@@ -47,20 +48,84 @@ pub fn entry(withdrawal_credentials: B256) {
         max_validator_index
     ));
 
+    assert!(
+        withdrawal_credentials.is_canonical(),
+        "Withdrawal credentials must be sorted and unique"
+    );
+    let withdrawal_credentials_hash = withdrawal_credentials.hash();
+
+    if let ProofType::Merge {
+        chunks,
+        chunk_receipts,
+    } = proof_type
+    {
+        env::log(&format!("Merging {} chunk proofs", chunks.len()));
+        let membership = merge_chunks(
+            network,
+            self_program_id,
+            withdrawal_credentials_hash,
+            state_root,
+            fork,
+            max_validator_index,
+            chunks,
+            chunk_receipts,
+        );
+        let journal = Journal {
+            self_program_id,
+            withdrawal_credential_prefixes: network.withdrawal_credential_prefixes.clone(),
+            withdrawal_credentials_hash,
+            state_root,
+            fork,
+            max_validator_index,
+            membership,
+        };
+        env::commit(&journal);
+        env::log("Execution complete");
+        return;
+    }
+
+    let beacon_state_gindices = network.beacon_state_gindices(fork);
+
     // verify the multi-proof which verifies leaf values
     env::log("Verifying SSZ multiproof");
+    let multiproof = multiproof.expect("Missing multiproof");
     multiproof
         .verify(&state_root)
         .expect("Failed to verify multiproof");
     let mut values = multiproof.values();
 
+    // the fork is supplied by the prover and committed to the journal so it must be the fork
+    // the network schedules at the state's slot, not just one with the same layout
+    let slot = values
+        .next_assert_gindex(beacon_state_gindices.slot())
+        .unwrap();
+    let slot = u64::from_le_bytes(slot[..8].try_into().unwrap());
+    assert_eq!(
+        network.fork_at_slot(slot),
+        Some(fork),
+        "Fork {fork:?} is not active at slot {slot}"
+    );
+
+    let is_chunk = matches!(proof_type, ProofType::Chunk { .. });
+
     let (start_validator_index, mut membership) = match proof_type {
         ProofType::Initial => (0, BitVec::<u32, Lsb0>::new()),
+        ProofType::Chunk {
+            start_validator_index,
+        } => {
+            env::log(&format!(
+                "Generating chunk proof from validator index {}",
+                start_validator_index
+            ));
+            (start_validator_index, BitVec::<u32, Lsb0>::new())
+        }
+        ProofType::Merge { .. } => unreachable!("Merge proofs are handled above"),
         ProofType::Continuation {
             prior_max_validator_index,
             prior_membership,
             cont_type,
             prior_slot,
+            prior_fork,
             prior_state_root,
         } => {
             env::log(&format!(
@@ -73,7 +138,7 @@ pub fn entry(withdrawal_credentials: B256) {
                 }
                 ShortRange => {
                     let stored_root = values
-                        .next_assert_gindex(beacon_state_gindices::state_roots(prior_slot))
+                        .next_assert_gindex(beacon_state_gindices.state_roots(prior_slot))
                         .unwrap();
                     assert_eq!(stored_root, &prior_state_root);
                 }
@@ -83,7 +148,7 @@ pub fn entry(withdrawal_credentials: B256) {
                     );
                     let historical_summary_root =
                         multiproof // using a get here for now but this does cause an extra iteration through the values
-                            .get(beacon_state_gindices::historical_summaries(
+                            .get(beacon_state_gindices.historical_summaries(
                                 prior_slot,
                             ))
                             .unwrap();
@@ -91,7 +156,7 @@ pub fn entry(withdrawal_credentials: B256) {
                         .verify(&historical_summary_root)
                         .expect("Failed to verify historical summary multiproof given the root in the current state");
                     let stored_root = hist_summary_multiproof
-                        .get(historical_batch_gindices::state_roots(prior_slot))
+                        .get(beacon_state_gindices.historical_batch_state_roots(prior_slot))
                         .unwrap();
                     assert_eq!(stored_root, &prior_state_root);
                 }
@@ -99,7 +164,10 @@ pub fn entry(withdrawal_credentials: B256) {
             // ensure the values in the journal match
             let prior_proof_journal = Journal {
                 self_program_id,
+                withdrawal_credential_prefixes: network.withdrawal_credential_prefixes.clone(),
+                withdrawal_credentials_hash,
                 state_root: prior_state_root,
+                fork: prior_fork,
                 max_validator_index: prior_max_validator_index,
                 membership: prior_membership,
             };
@@ -137,19 +205,104 @@ pub fn entry(withdrawal_credentials: B256) {
     env::log("Enumerating validators");
     for validator_index in start_validator_index..=max_validator_index {
         let value = values
-            .next_assert_gindex(beacon_state_gindices::validator_withdrawal_credentials(
-                validator_index,
-            ))
+            .next_assert_gindex(
+                beacon_state_gindices.validator_withdrawal_credentials(validator_index),
+            )
             .unwrap();
-        membership.push(value == &withdrawal_credentials);
+        membership
+            .push(withdrawal_credentials.matches(value, &network.withdrawal_credential_prefixes));
     }
 
-    let journal = Journal {
-        self_program_id,
-        state_root,
-        max_validator_index,
-        membership,
-    };
-    env::commit(&journal);
+    if is_chunk {
+        let journal = ChunkJournal {
+            self_program_id,
+            withdrawal_credential_prefixes: network.withdrawal_credential_prefixes.clone(),
+            withdrawal_credentials_hash,
+            state_root,
+            fork,
+            start_validator_index,
+            max_validator_index,
+            membership,
+        };
+        env::commit_slice(&journal.to_bytes().unwrap());
+    } else {
+        let journal = Journal {
+            self_program_id,
+            withdrawal_credential_prefixes: network.withdrawal_credential_prefixes.clone(),
+            withdrawal_credentials_hash,
+            state_root,
+            fork,
+            max_validator_index,
+            membership,
+        };
+        env::commit(&journal);
+    }
     env::log("Execution complete");
 }
+
+/// Verifies chunk proofs of the same state and concatenates their membership.
+/// The chunks must be contiguous and together cover validators 0..=max_validator_index
+#[cfg_attr(feature = "skip-verify", allow(unused_variables))]
+fn merge_chunks(
+    network: &NetworkConfig,
+    self_program_id: Digest,
+    withdrawal_credentials_hash: B256,
+    state_root: B256,
+    fork: Fork,
+    max_validator_index: u64,
+    chunks: Vec<ChunkJournal>,
+    chunk_receipts: Vec<Receipt>,
+) -> BitVec<u32, Lsb0> {
+    assert!(!chunks.is_empty(), "At least one chunk is required");
+    assert_eq!(
+        chunks.len(),
+        chunk_receipts.len(),
+        "Every chunk requires a receipt"
+    );
+
+    let mut membership =
+        BitVec::<u32, Lsb0>::with_capacity((max_validator_index + 1).try_into().unwrap());
+    let mut next_validator_index = 0;
+
+    for (chunk, receipt) in chunks.into_iter().zip(chunk_receipts) {
+        assert_eq!(chunk.self_program_id, self_program_id);
+        assert_eq!(
+            chunk.withdrawal_credential_prefixes,
+            network.withdrawal_credential_prefixes
+        );
+        assert_eq!(
+            chunk.withdrawal_credentials_hash,
+            withdrawal_credentials_hash
+        );
+        assert_eq!(chunk.state_root, state_root);
+        assert_eq!(chunk.fork, fork);
+        assert_eq!(
+            chunk.start_validator_index, next_validator_index,
+            "Chunks must be contiguous and start at validator index 0"
+        );
+        assert!(chunk.max_validator_index >= chunk.start_validator_index);
+        assert_eq!(
+            chunk.membership.len() as u64,
+            chunk.max_validator_index - chunk.start_validator_index + 1
+        );
+
+        #[cfg(not(feature = "skip-verify"))]
+        {
+            assert_eq!(receipt.journal.bytes, chunk.to_bytes().unwrap());
+            receipt
+                .verify(self_program_id)
+                .expect("Failed to verify chunk receipt");
+        }
+
+        next_validator_index = chunk.max_validator_index + 1;
+        membership.extend_from_bitslice(&chunk.membership);
+    }
+
+    assert_eq!(
+        next_validator_index,
+        max_validator_index + 1,
+        "Chunks must cover validators up to the max validator index"
+    );
+
+    membership
+}
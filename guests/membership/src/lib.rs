@@ -17,23 +17,32 @@ include!(concat!(env!("OUT_DIR"), "/methods.rs"));
 
 #[cfg(test)]
 mod tests {
-    use gindices::presets::mainnet::beacon_state::{CAPELLA_FORK_SLOT, SLOTS_PER_HISTORICAL_ROOT};
-    use guest_io::{mainnet::WITHDRAWAL_CREDENTIALS, validator_membership};
+    use guest_io::{
+        network::{MAINNET, SEPOLIA},
+        validator_membership, ConsensusPreset, CredentialSet, Fork, NetworkConfig,
+    };
     use risc0_zkvm::{default_executor, ExecutorEnv, ExitCode, LocalProver, Prover};
     use test_utils::TestStateBuilder;
 
+    /// First slot of the Electra fork on mainnet. States built by [TestStateBuilder::build] must be at or after it
+    fn electra_fork_slot() -> u64 {
+        MAINNET.electra_fork_slot.unwrap()
+    }
+
     #[test]
     fn test_initial_proof() -> anyhow::Result<()> {
         let n_validators = 11;
         let n_lido_validators = 10;
         let max_validator_index = n_validators + n_lido_validators - 1;
 
-        let mut b = TestStateBuilder::new(CAPELLA_FORK_SLOT);
+        let mut b = TestStateBuilder::new(electra_fork_slot());
         b.with_validators(n_validators);
         b.with_lido_validators(n_lido_validators);
         let s = b.build();
 
         let input = validator_membership::Input::build_initial(
+            &MAINNET,
+            MAINNET.credential_set(),
             s,
             max_validator_index as u64,
             super::MAINNET_ID,
@@ -55,17 +64,146 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_compounding_credentials() -> anyhow::Result<()> {
+        let n_validators = 5;
+        let n_lido_validators = 3;
+        let n_compounding_lido_validators = 4;
+        let max_validator_index =
+            n_validators + n_lido_validators + n_compounding_lido_validators - 1;
+
+        let mut b = TestStateBuilder::new(electra_fork_slot());
+        b.with_validators(n_validators);
+        b.with_lido_validators(n_lido_validators);
+        b.with_compounding_lido_validators(n_compounding_lido_validators);
+        let s = b.build();
+
+        let input = validator_membership::Input::build_initial(
+            &MAINNET,
+            MAINNET.credential_set(),
+            s,
+            max_validator_index as u64,
+            super::MAINNET_ID,
+        )?
+        .without_receipt();
+        let env = ExecutorEnv::builder()
+            .write_frame(&bincode::serialize(&input).unwrap())
+            .build()?;
+        let session_info = default_executor().execute(env, super::MAINNET_ELF)?;
+
+        let journal = session_info
+            .journal
+            .decode::<validator_membership::Journal>()?;
+        assert_eq!(
+            journal.membership.count_ones(),
+            n_lido_validators + n_compounding_lido_validators
+        );
+        assert_eq!(
+            journal.withdrawal_credential_prefixes,
+            MAINNET.withdrawal_credential_prefixes
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_multiple_credentials() -> anyhow::Result<()> {
+        let other_credentials = SEPOLIA.withdrawal_credentials;
+        let credentials = CredentialSet::new([MAINNET.withdrawal_credentials, other_credentials]);
+
+        let mut b = TestStateBuilder::new(electra_fork_slot());
+        b.with_validators(3);
+        b.with_lido_validators(2);
+        b.with_credentialed_validators(4, other_credentials);
+        b.with_validators(1);
+        let s = b.build();
+
+        let input = validator_membership::Input::build_initial(
+            &MAINNET,
+            credentials.clone(),
+            s,
+            9,
+            super::MAINNET_ID,
+        )?
+        .without_receipt();
+        let env = ExecutorEnv::builder()
+            .write_frame(&bincode::serialize(&input).unwrap())
+            .build()?;
+        let session_info = default_executor().execute(env, super::MAINNET_ELF)?;
+
+        let journal = session_info
+            .journal
+            .decode::<validator_membership::Journal>()?;
+        assert_eq!(journal.membership.count_ones(), 6);
+        assert_eq!(journal.withdrawal_credentials_hash, credentials.hash());
+        Ok(())
+    }
+
+    #[test]
+    fn test_chunked_proof() -> anyhow::Result<()> {
+        let mut b = TestStateBuilder::new(electra_fork_slot());
+        b.with_validators(4);
+        b.with_lido_validators(5);
+        b.with_validators(2);
+        let s = b.build();
+
+        let ranges = [(0, 3), (4, 7), (8, 10)];
+        let inputs = validator_membership::Input::build_chunks(
+            &MAINNET,
+            MAINNET.credential_set(),
+            &s,
+            &ranges,
+            super::MAINNET_ID,
+        )?;
+        let mut chunks = Vec::new();
+        for ((start, _), input) in ranges.into_iter().zip(inputs) {
+            let input = input.without_receipt();
+            let env = ExecutorEnv::builder()
+                .write_frame(&bincode::serialize(&input).unwrap())
+                .build()?;
+            let prove_info = LocalProver::new("test").prove(env, super::MAINNET_ELF)?;
+            let journal =
+                validator_membership::ChunkJournal::from_bytes(&prove_info.receipt.journal.bytes)?;
+            assert_eq!(journal.start_validator_index, start);
+            chunks.push((journal, prove_info.receipt));
+        }
+
+        let input = validator_membership::Input::build_merge(
+            MAINNET.credential_set(),
+            chunks,
+            super::MAINNET_ID,
+        )?
+        .without_receipt();
+        let env = ExecutorEnv::builder()
+            .write_frame(&bincode::serialize(&input).unwrap())
+            .build()?;
+        let session_info = default_executor().execute(env, super::MAINNET_ELF)?;
+
+        let journal = session_info
+            .journal
+            .decode::<validator_membership::Journal>()?;
+        assert_eq!(journal.max_validator_index, 10);
+        assert_eq!(journal.membership.len(), 11);
+        assert_eq!(journal.membership.count_ones(), 5);
+        Ok(())
+    }
+
     #[test]
     fn test_continuation_same_slot() -> anyhow::Result<()> {
         let n_validators = 11;
         let max_validator_index = n_validators - 1;
 
-        let mut b = TestStateBuilder::new(CAPELLA_FORK_SLOT);
+        let mut b = TestStateBuilder::new(electra_fork_slot());
         b.with_validators(n_validators);
         let s1 = b.build();
 
-        let input = validator_membership::Input::build_initial(s1.clone(), 5, super::MAINNET_ID)?
-            .without_receipt();
+        let input = validator_membership::Input::build_initial(
+            &MAINNET,
+            MAINNET.credential_set(),
+            s1.clone(),
+            5,
+            super::MAINNET_ID,
+        )?
+        .without_receipt();
         let env = ExecutorEnv::builder()
             .write_frame(&bincode::serialize(&input).unwrap())
             .build()?;
@@ -73,7 +211,8 @@ mod tests {
         prove_info.receipt.verify(super::MAINNET_ID)?;
 
         let input = validator_membership::Input::build_continuation(
-            WITHDRAWAL_CREDENTIALS,
+            &MAINNET,
+            MAINNET.credential_set(),
             &s1,
             5,
             &s1,
@@ -97,17 +236,23 @@ mod tests {
         let n_validators = 11;
         let max_validator_index = n_validators - 1;
 
-        let mut b = TestStateBuilder::new(CAPELLA_FORK_SLOT);
+        let mut b = TestStateBuilder::new(electra_fork_slot());
         b.with_validators(n_validators);
         let s1 = b.build();
 
-        let mut b = TestStateBuilder::new(CAPELLA_FORK_SLOT + 20);
+        let mut b = TestStateBuilder::new(electra_fork_slot() + 20);
         b.with_validators(n_validators + 10);
         b.with_prior_state(&s1);
         let s2 = b.build();
 
-        let input = validator_membership::Input::build_initial(s1.clone(), 5, super::MAINNET_ID)?
-            .without_receipt();
+        let input = validator_membership::Input::build_initial(
+            &MAINNET,
+            MAINNET.credential_set(),
+            s1.clone(),
+            5,
+            super::MAINNET_ID,
+        )?
+        .without_receipt();
 
         let env = ExecutorEnv::builder()
             .write_frame(&bincode::serialize(&input).unwrap())
@@ -115,7 +260,8 @@ mod tests {
         let prove_info = LocalProver::new("test").prove(env, super::MAINNET_ELF)?;
 
         let input = validator_membership::Input::build_continuation(
-            WITHDRAWAL_CREDENTIALS,
+            &MAINNET,
+            MAINNET.credential_set(),
             &s1,
             5,
             &s2,
@@ -139,17 +285,24 @@ mod tests {
         let n_validators = 11;
         let max_validator_index = n_validators - 1;
 
-        let mut b = TestStateBuilder::new(CAPELLA_FORK_SLOT);
+        let mut b = TestStateBuilder::new(electra_fork_slot());
         b.with_validators(n_validators);
         let s1 = b.build();
 
-        let mut b = TestStateBuilder::new(CAPELLA_FORK_SLOT + SLOTS_PER_HISTORICAL_ROOT + 1);
+        let mut b =
+            TestStateBuilder::new(electra_fork_slot() + MAINNET.slots_per_historical_root + 1);
         b.with_validators(n_validators + 10);
         let hist_batch = b.with_prior_state(&s1);
         let s2 = b.build();
 
-        let input = validator_membership::Input::build_initial(s1.clone(), 5, super::MAINNET_ID)?
-            .without_receipt();
+        let input = validator_membership::Input::build_initial(
+            &MAINNET,
+            MAINNET.credential_set(),
+            s1.clone(),
+            5,
+            super::MAINNET_ID,
+        )?
+        .without_receipt();
 
         let env = ExecutorEnv::builder()
             .write_frame(&bincode::serialize(&input).unwrap())
@@ -157,7 +310,8 @@ mod tests {
         let prove_info = LocalProver::new("test").prove(env, super::MAINNET_ELF)?;
 
         let input = validator_membership::Input::build_continuation(
-            WITHDRAWAL_CREDENTIALS,
+            &MAINNET,
+            MAINNET.credential_set(),
             &s1,
             5,
             &s2,
@@ -175,4 +329,127 @@ mod tests {
         assert_eq!(session_info.exit_code, ExitCode::Halted(0));
         Ok(())
     }
+
+    #[test]
+    fn test_continuation_across_electra() -> anyhow::Result<()> {
+        let n_validators = 11;
+        let max_validator_index = n_validators - 1;
+
+        let mut b = TestStateBuilder::new(electra_fork_slot() - 10);
+        b.with_validators(n_validators);
+        b.with_lido_validators(2);
+        let s1 = b.build_deneb();
+
+        let mut b = TestStateBuilder::new(electra_fork_slot() + 10);
+        b.with_validators(n_validators);
+        b.with_lido_validators(2);
+        let hist_batch = b.with_prior_state(&s1);
+        let s2 = b.build();
+
+        let input = validator_membership::Input::build_initial(
+            &MAINNET,
+            MAINNET.credential_set(),
+            s1.clone(),
+            5,
+            super::MAINNET_ID,
+        )?
+        .without_receipt();
+        assert_eq!(input.input.fork, Fork::Deneb);
+
+        let env = ExecutorEnv::builder()
+            .write_frame(&bincode::serialize(&input).unwrap())
+            .build()?;
+        let prove_info = LocalProver::new("test").prove(env, super::MAINNET_ELF)?;
+
+        let input = validator_membership::Input::build_continuation(
+            &MAINNET,
+            MAINNET.credential_set(),
+            &s1,
+            5,
+            &s2,
+            max_validator_index as u64,
+            hist_batch,
+            super::MAINNET_ID,
+        )?
+        .with_receipt(prove_info.receipt);
+        assert_eq!(input.input.fork, Fork::Electra);
+
+        let env = ExecutorEnv::builder()
+            .write_frame(&bincode::serialize(&input).unwrap())
+            .build()?;
+
+        let session_info = default_executor().execute(env, super::MAINNET_ELF)?;
+        assert_eq!(session_info.exit_code, ExitCode::Halted(0));
+        let journal = session_info
+            .journal
+            .decode::<validator_membership::Journal>()?;
+        assert_eq!(journal.fork, Fork::Electra);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fork_must_match_slot() -> anyhow::Result<()> {
+        // an Electra state before the Electra fork slot would make the guest read the wrong gindices
+        let mut b = TestStateBuilder::new(electra_fork_slot() - 1);
+        b.with_validators(3);
+        let s = b.build();
+
+        let result = validator_membership::Input::build_initial(
+            &MAINNET,
+            MAINNET.credential_set(),
+            s,
+            2,
+            super::MAINNET_ID,
+        );
+        assert!(matches!(result, Err(guest_io::Error::ForkMismatch { .. })));
+        Ok(())
+    }
+
+    #[test]
+    fn test_preset_must_match_network() -> anyhow::Result<()> {
+        // a mainnet state on a minimal preset network would be proven with the wrong gindices
+        let network = NetworkConfig {
+            preset: ConsensusPreset::Minimal,
+            ..MAINNET.clone()
+        };
+        let mut b = TestStateBuilder::new(electra_fork_slot());
+        b.with_validators(3);
+        let s = b.build();
+
+        let result = validator_membership::Input::build_initial(
+            &network,
+            network.credential_set(),
+            s,
+            2,
+            super::MAINNET_ID,
+        );
+        assert!(matches!(
+            result,
+            Err(guest_io::Error::PresetMismatch { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_fork_is_bound_to_slot() -> anyhow::Result<()> {
+        // Capella and Deneb states share a layout, so only the fork schedule stops a prover from
+        // committing the wrong one of them
+        let mut b = TestStateBuilder::new(electra_fork_slot() - 1);
+        b.with_validators(3);
+        let s = b.build_deneb();
+
+        let mut input = validator_membership::Input::build_initial(
+            &MAINNET,
+            MAINNET.credential_set(),
+            s,
+            2,
+            super::MAINNET_ID,
+        )?;
+        input.fork = Fork::Capella;
+        let env = ExecutorEnv::builder()
+            .write_frame(&bincode::serialize(&input.without_receipt()).unwrap())
+            .build()?;
+        assert!(default_executor().execute(env, super::MAINNET_ELF).is_err());
+        Ok(())
+    }
 }